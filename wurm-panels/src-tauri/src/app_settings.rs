@@ -1,11 +1,34 @@
-use directories::ProjectDirs;
+use crate::granger_traits::TraitWeights;
+use crate::log_discovery::{self, WatchDirDiagnostics};
+use crate::logging;
+use crate::persistence;
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
 
 pub const SETTINGS_FILE_NAME: &str = "settings.json";
 
+pub const DEFAULT_PROFILE_NAME: &str = "Default";
+
+/// Bump alongside a new entry in `MIGRATIONS` whenever the layout changes.
+pub const SETTINGS_SCHEMA_VERSION: u32 = 4;
+
+// Files written before the schema was versioned, or with a version of 0.
+const UNVERSIONED_SCHEMA: u32 = 1;
+
+type Migration = fn(&mut Value);
+
+// Each entry upgrades a settings file from the given version to the next.
+const MIGRATIONS: &[(u32, Migration)] = &[
+    (1, migrate_v1_to_v2),
+    (2, migrate_v2_to_v3),
+    (3, migrate_v3_to_v4),
+];
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct WindowPlacement {
     pub x: f64,
@@ -25,71 +48,389 @@ impl Default for WindowPlacement {
     }
 }
 
+/// What is remembered about a panel between runs, keyed by window label.
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct WindowState {
+    #[serde(default)]
+    pub placement: Option<WindowPlacement>,
+    #[serde(default)]
+    pub always_on_top: Option<bool>,
+    /// Applied by the panel itself, from 0.2 to 1.0.
+    #[serde(default)]
+    pub opacity: Option<f64>,
+    /// Whether the panel was open when last used, so it reopens at startup.
+    #[serde(default)]
+    pub visible: bool,
+}
+
+/// Settings that belong to one character rather than the whole app.
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct SettingsProfile {
+    #[serde(default)]
+    pub watch_dir: String,
+    #[serde(default)]
+    pub windows: BTreeMap<String, WindowState>,
+    /// Item names the trade panel highlights.
+    #[serde(default)]
+    pub trade_watchlist: Vec<String>,
+    /// Target level per skill name.
+    #[serde(default)]
+    pub skill_targets: BTreeMap<String, f64>,
+}
+
+/// The active profile's values live in the top-level fields, which is what
+/// the watcher and windows read. Its entry in `profiles` is refreshed when
+/// switching away, so it can lag behind until then.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AppSettings {
+    #[serde(default)]
+    pub schema_version: u32,
     pub watch_dir: String,
     #[serde(default)]
-    pub skills_window: WindowPlacement,
+    pub windows: BTreeMap<String, WindowState>,
+    #[serde(default)]
+    pub trade_watchlist: Vec<String>,
+    #[serde(default)]
+    pub skill_targets: BTreeMap<String, f64>,
+    #[serde(default)]
+    pub trait_weights: TraitWeights,
+    #[serde(default = "default_profile_name")]
+    pub active_profile: String,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, SettingsProfile>,
+    /// Follow whichever profile's character is writing logs.
+    #[serde(default)]
+    pub auto_switch_profiles: bool,
+    /// Log levels, optionally per module, e.g. `info,wurm_panels::watcher=debug`.
+    #[serde(default = "default_log_filter")]
+    pub log_filter: String,
+}
+
+fn default_profile_name() -> String {
+    DEFAULT_PROFILE_NAME.to_string()
+}
+
+fn default_log_filter() -> String {
+    logging::DEFAULT_LOG_FILTER.to_string()
 }
 
 impl AppSettings {
+    /// Defaults to the most recently active character's logs, if any Wurm
+    /// install can be found.
     pub fn default() -> Self {
-        Self {
-            watch_dir: "C:\\Users\\johnw\\wurm\\players\\jackjones\\logs".to_string(),
-            skills_window: WindowPlacement::default(),
+        let mut settings = Self {
+            schema_version: SETTINGS_SCHEMA_VERSION,
+            watch_dir: log_discovery::discover().suggested.unwrap_or_default(),
+            windows: BTreeMap::new(),
+            trade_watchlist: Vec::new(),
+            skill_targets: BTreeMap::new(),
+            trait_weights: TraitWeights::new(),
+            active_profile: default_profile_name(),
+            profiles: BTreeMap::new(),
+            auto_switch_profiles: false,
+            log_filter: default_log_filter(),
+        };
+        settings.stash_profile();
+        settings
+    }
+
+    /// Copies the top-level values into the active profile's entry.
+    pub fn stash_profile(&mut self) {
+        self.profiles.insert(
+            self.active_profile.clone(),
+            SettingsProfile {
+                watch_dir: self.watch_dir.clone(),
+                windows: self.windows.clone(),
+                trade_watchlist: self.trade_watchlist.clone(),
+                skill_targets: self.skill_targets.clone(),
+            },
+        );
+    }
+
+    /// Makes `name` the active profile. A new profile starts with the
+    /// current window layout and no watch directory. Returns false if the
+    /// profile was already active.
+    pub fn switch_profile(&mut self, name: &str) -> bool {
+        if name == self.active_profile {
+            return false;
         }
+        self.stash_profile();
+
+        let profile = self.profiles.get(name).cloned().unwrap_or(SettingsProfile {
+            windows: self.windows.clone(),
+            ..SettingsProfile::default()
+        });
+        self.active_profile = name.to_string();
+        self.watch_dir = profile.watch_dir;
+        self.windows = profile.windows;
+        self.trade_watchlist = profile.trade_watchlist;
+        self.skill_targets = profile.skill_targets;
+        self.stash_profile();
+        true
+    }
+
+    pub fn delete_profile(&mut self, name: &str) -> Result<(), String> {
+        if name == self.active_profile {
+            return Err("Cannot delete the active profile".to_string());
+        }
+        self.profiles
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| format!("Profile not found: {}", name))
     }
 }
 
 pub type SharedSettings = Arc<Mutex<AppSettings>>;
 
+/// Result of `update_settings`. A watch directory that fails validation is
+/// reported back instead of saved.
+#[derive(Clone, Serialize)]
+pub struct SettingsUpdate {
+    pub saved: bool,
+    pub settings: AppSettings,
+    pub watch_dir: Option<WatchDirDiagnostics>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingsNoticeKind {
+    /// An older settings file was upgraded in place.
+    Migrated,
+    /// The settings file could not be read and defaults were applied.
+    Recovered,
+}
+
+/// Tells the user what happened to their settings file at startup.
+#[derive(Clone, Serialize)]
+pub struct SettingsNotice {
+    pub kind: SettingsNoticeKind,
+    pub message: String,
+    pub from_version: Option<u32>,
+    pub backup_path: Option<String>,
+}
+
+pub type SharedSettingsNotice = Arc<Mutex<Option<SettingsNotice>>>;
+
+pub struct LoadedSettings {
+    pub settings: AppSettings,
+    pub notice: Option<SettingsNotice>,
+}
+
 pub fn new_shared(settings: AppSettings) -> SharedSettings {
     Arc::new(Mutex::new(settings))
 }
 
-fn config_dir_path() -> Result<PathBuf, String> {
-    ProjectDirs::from("com", "WefNET", "wurm-sales")
-        .map(|dirs| dirs.config_dir().to_path_buf())
-        .ok_or_else(|| "Unable to resolve configuration directory".to_string())
-}
-
-pub fn load_settings_from_disk() -> AppSettings {
-    match config_dir_path() {
-        Ok(config_dir) => {
-            let settings_path = config_dir.join(SETTINGS_FILE_NAME);
-            if let Ok(raw) = fs::read_to_string(&settings_path) {
-                if let Ok(settings) = serde_json::from_str::<AppSettings>(&raw) {
-                    return settings;
-                } else {
-                    println!("Failed to deserialize settings, using defaults");
-                }
-            } else {
-                println!("Settings file not found, using defaults");
+pub fn load_settings_from_disk() -> LoadedSettings {
+    let defaults = || LoadedSettings {
+        settings: AppSettings::default(),
+        notice: None,
+    };
+
+    let raw = match persistence::read_raw(SETTINGS_FILE_NAME) {
+        Ok(Some(raw)) => raw,
+        Ok(None) => {
+            info!("Settings file not found, using defaults");
+            return defaults();
+        }
+        Err(err) => {
+            info!("{}, using defaults", err);
+            return defaults();
+        }
+    };
+
+    match migrate_settings(&raw) {
+        Ok((settings, from_version)) if from_version < SETTINGS_SCHEMA_VERSION => {
+            // Keep the old layout around in case the upgrade loses something.
+            let backup_path = backup_settings_file(&raw, &format!("v{}", from_version), false);
+            if let Err(err) = persist_settings(&settings) {
+                warn!("Failed to save migrated settings: {}", err);
+            }
+            info!(
+                "Migrated settings from schema {} to {}",
+                from_version, SETTINGS_SCHEMA_VERSION
+            );
+            LoadedSettings {
+                settings,
+                notice: Some(SettingsNotice {
+                    kind: SettingsNoticeKind::Migrated,
+                    message: format!(
+                        "Settings were upgraded from version {} to {}.",
+                        from_version, SETTINGS_SCHEMA_VERSION
+                    ),
+                    from_version: Some(from_version),
+                    backup_path,
+                }),
             }
-            AppSettings::default()
         }
+        Ok((settings, _)) => LoadedSettings {
+            settings,
+            notice: None,
+        },
         Err(err) => {
-            println!("{}", err);
-            AppSettings::default()
+            warn!("Failed to deserialize settings ({}), using defaults", err);
+            // Move the file aside so the next save does not overwrite it.
+            let backup_path = backup_settings_file(&raw, "unreadable", true);
+            LoadedSettings {
+                settings: AppSettings::default(),
+                notice: Some(SettingsNotice {
+                    kind: SettingsNoticeKind::Recovered,
+                    message: format!(
+                        "Settings could not be read and were reset to defaults: {}",
+                        err
+                    ),
+                    from_version: None,
+                    backup_path,
+                }),
+            }
         }
     }
 }
 
-pub fn persist_settings(settings: &AppSettings) -> Result<(), String> {
-    let config_dir = config_dir_path()?;
+/// Parses a settings file of any known schema version, upgrading it to the
+/// current layout. Returns the settings and the version the file was at.
+pub fn migrate_settings(raw: &str) -> Result<(AppSettings, u32), String> {
+    let mut value: Value = serde_json::from_str(raw).map_err(|e| e.to_string())?;
+    if !value.is_object() {
+        return Err("settings file does not hold an object".to_string());
+    }
+
+    let from_version = value
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .map(|version| version as u32)
+        .unwrap_or(UNVERSIONED_SCHEMA)
+        .max(UNVERSIONED_SCHEMA);
+    if from_version > SETTINGS_SCHEMA_VERSION {
+        warn!(
+            "Settings were written by a newer version (schema {}); reading known fields",
+            from_version
+        );
+    }
+
+    let mut version = from_version;
+    while let Some((_, migrate)) = MIGRATIONS.iter().find(|(from, _)| *from == version) {
+        migrate(&mut value);
+        version += 1;
+    }
+    if version < SETTINGS_SCHEMA_VERSION {
+        return Err(format!("no migration from settings schema {}", version));
+    }
+    value["schema_version"] = Value::from(version.max(from_version));
+
+    let settings = serde_json::from_value::<AppSettings>(value).map_err(|e| e.to_string())?;
+    Ok((settings, from_version))
+}
+
+// Version 2 adds the schema version and trait weights. Early builds also
+// wrote a null watch_dir before one was chosen.
+fn migrate_v1_to_v2(value: &mut Value) {
+    if !value.get("watch_dir").is_some_and(Value::is_string) {
+        value["watch_dir"] = Value::from("");
+    }
+    if !value.get("trait_weights").is_some_and(Value::is_object) {
+        value["trait_weights"] = Value::Object(Default::default());
+    }
+}
 
-    if !config_dir.exists() {
-        fs::create_dir_all(&config_dir)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+// Version 3 replaces `skills_window` with per-window state for every panel.
+// The old size was the physical outer size, while placements hold the logical
+// inner size, and the scale factor is unknown here, so only the position is
+// kept.
+fn migrate_v2_to_v3(value: &mut Value) {
+    let Some(object) = value.as_object_mut() else {
+        return;
+    };
+    let skills = object.remove("skills_window");
+    let windows = object
+        .entry("windows")
+        .or_insert_with(|| Value::Object(Default::default()));
+    if let (Some(skills), Some(windows)) = (skills, windows.as_object_mut()) {
+        let default = WindowPlacement::default();
+        let placement = serde_json::json!({
+            "x": skills.get("x").and_then(Value::as_f64).unwrap_or(default.x),
+            "y": skills.get("y").and_then(Value::as_f64).unwrap_or(default.y),
+            "width": default.width,
+            "height": default.height,
+        });
+        windows.insert(
+            "skills".to_string(),
+            serde_json::json!({ "placement": placement, "visible": false }),
+        );
     }
+}
 
-    let settings_path = config_dir.join(SETTINGS_FILE_NAME);
-    let serialized = serde_json::to_string_pretty(settings)
-        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+// Version 4 groups the per-character values into named profiles, starting
+// with one holding whatever was configured before.
+fn migrate_v3_to_v4(value: &mut Value) {
+    let Some(object) = value.as_object_mut() else {
+        return;
+    };
+    let profile = serde_json::json!({
+        "watch_dir": object.get("watch_dir").cloned().unwrap_or_else(|| Value::from("")),
+        "windows": object.get("windows").cloned().unwrap_or_else(|| serde_json::json!({})),
+    });
+    object
+        .entry("profiles")
+        .or_insert_with(|| serde_json::json!({ DEFAULT_PROFILE_NAME: profile }));
+    object
+        .entry("active_profile")
+        .or_insert_with(|| Value::from(DEFAULT_PROFILE_NAME));
+}
+
+/// Writes the settings file's contents to a timestamped sibling, removing the
+/// original when `move_aside` is set, and returns where the copy went.
+fn backup_settings_file(raw: &str, reason: &str, move_aside: bool) -> Option<String> {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let backup_name = format!("settings.{}-{}.json", reason, stamp);
 
-    fs::write(&settings_path, serialized)
-        .map_err(|e| format!("Failed to write settings file: {}", e))?;
+    let result = persistence::write_raw(&backup_name, raw).and_then(|backup_path| {
+        if move_aside {
+            persistence::remove(SETTINGS_FILE_NAME)?;
+        }
+        Ok(backup_path)
+    });
+    match result {
+        Ok(backup_path) => {
+            info!("Backed up settings to {:?}", backup_path);
+            Some(backup_path.to_string_lossy().to_string())
+        }
+        Err(err) => {
+            warn!("Failed to back up settings file: {}", err);
+            None
+        }
+    }
+}
 
-    println!("Settings saved to {:?}", settings_path);
+pub fn persist_settings(settings: &AppSettings) -> Result<(), String> {
+    let settings_path = persistence::save(SETTINGS_FILE_NAME, settings)?;
+    info!("Settings saved to {:?}", settings_path);
     Ok(())
 }
+
+/// Sends updated settings to every window that shows them.
+pub fn broadcast(app: &AppHandle, settings: &AppSettings) {
+    if let Err(err) = app.emit("settings-updated", settings.clone()) {
+        warn!("Failed to emit settings update to main window: {:?}", err);
+    }
+
+    if let Some(watcher_window) = app.get_webview_window("watcher") {
+        if let Err(err) = watcher_window.emit("settings-updated", settings.clone()) {
+            warn!(
+                "Failed to emit settings update to watcher window: {:?}",
+                err
+            );
+        }
+    }
+
+    if let Some(settings_window) = app.get_webview_window("settings") {
+        if let Err(err) = settings_window.emit("settings-data", settings.clone()) {
+            warn!(
+                "Failed to emit settings update to settings window: {:?}",
+                err
+            );
+        }
+    }
+}
@@ -0,0 +1,446 @@
+use crate::app_settings::{
+    migrate_settings, AppSettings, SETTINGS_FILE_NAME, SETTINGS_SCHEMA_VERSION,
+};
+use crate::database;
+use crate::granger::{unix_now, GrangerAnimal};
+use crate::granger_exchange::{self, ImportReport};
+use crate::merchant_sales::MERCHANT_SALES_FILE_NAME;
+use crate::persistence;
+use crate::price_history::PRICE_HISTORY_FILE_NAME;
+use crate::user_layers_persistence::UserLayer;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+use tracing::warn;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Bump when the archive layout changes. Restores refuse newer archives.
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_NAME: &str = "manifest.json";
+const SETTINGS_ENTRY: &str = "settings.json";
+const GRANGER_ENTRY: &str = "granger.json";
+const USER_LAYERS_DIR: &str = "user_layers/";
+const DATA_DIR: &str = "data/";
+
+// Cached community map data, one file per map and kind.
+const COMMUNITY_CACHE_PREFIX: &str = "community_";
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupEntryKind {
+    Settings,
+    Granger,
+    UserLayers,
+    DataFile,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub path: String,
+    pub kind: BackupEntryKind,
+    /// Records in the entry, for lists.
+    pub records: Option<usize>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: i64, // Unix timestamp
+    pub entries: Vec<BackupEntry>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RestoreMode {
+    /// Adds what this machine is missing and keeps local settings.
+    Merge,
+    /// Makes this machine's data match the archive.
+    Replace,
+}
+
+#[derive(Clone, Serialize)]
+pub struct RestoreReport {
+    pub mode: RestoreMode,
+    pub created_at: i64, // Unix timestamp the archive was made
+    pub settings_restored: bool,
+    pub granger: ImportReport,
+    pub user_layer_maps: usize,
+    pub data_files: usize,
+    /// Local data files the archive did not have, removed in replace mode.
+    pub removed_files: usize,
+}
+
+// Everything read out of an archive, parsed before anything is written.
+struct BackupContents {
+    manifest: BackupManifest,
+    settings: Option<AppSettings>,
+    granger: Option<Vec<GrangerAnimal>>,
+    user_layers: BTreeMap<String, Vec<UserLayer>>,
+    data_files: BTreeMap<String, Value>,
+}
+
+// JSON stores copied as they are. Community caches are found by prefix.
+fn is_data_file(file_name: &str) -> bool {
+    let plain = !file_name.contains(['/', '\\']) && file_name.ends_with(".json");
+    plain
+        && (file_name == MERCHANT_SALES_FILE_NAME
+            || file_name == PRICE_HISTORY_FILE_NAME
+            || file_name.starts_with(COMMUNITY_CACHE_PREFIX))
+}
+
+fn data_file_names() -> Vec<String> {
+    let Ok(entries) = persistence::config_dir_path()
+        .and_then(|dir| fs::read_dir(dir).map_err(|err| err.to_string()))
+    else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| is_data_file(name))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Writes settings, the granger herd, user layers for every map and the
+/// other data files to a single zip archive.
+pub fn export(
+    path: &str,
+    settings: &AppSettings,
+    granger: &[GrangerAnimal],
+) -> Result<BackupManifest, String> {
+    let mut files: Vec<(String, String)> = Vec::new();
+    let mut entries: Vec<BackupEntry> = Vec::new();
+    let mut add = |name: String, kind, records, contents: String| {
+        entries.push(BackupEntry {
+            path: name.clone(),
+            kind,
+            records,
+        });
+        files.push((name, contents));
+    };
+
+    add(
+        SETTINGS_ENTRY.to_string(),
+        BackupEntryKind::Settings,
+        None,
+        to_json(settings)?,
+    );
+    add(
+        GRANGER_ENTRY.to_string(),
+        BackupEntryKind::Granger,
+        Some(granger.len()),
+        to_json(granger)?,
+    );
+    for map_id in database::user_layer_map_ids()? {
+        let layers = database::user_layers(&map_id)?;
+        add(
+            format!("{}{}.json", USER_LAYERS_DIR, map_id),
+            BackupEntryKind::UserLayers,
+            Some(layers.len()),
+            to_json(&layers)?,
+        );
+    }
+    for file_name in data_file_names() {
+        let Some(raw) = persistence::read_raw(&file_name)? else {
+            continue;
+        };
+        let records = serde_json::from_str::<Value>(&raw)
+            .ok()
+            .and_then(|value| value.as_array().map(Vec::len));
+        add(
+            format!("{}{}", DATA_DIR, file_name),
+            BackupEntryKind::DataFile,
+            records,
+            raw,
+        );
+    }
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: unix_now(),
+        entries,
+    };
+
+    let file = File::create(path).map_err(|err| format!("Failed to create backup: {}", err))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    files.insert(0, (MANIFEST_NAME.to_string(), to_json(&manifest)?));
+    for (name, contents) in &files {
+        zip.start_file(name.as_str(), options)
+            .map_err(|err| err.to_string())
+            .and_then(|_| {
+                zip.write_all(contents.as_bytes())
+                    .map_err(|err| err.to_string())
+            })
+            .map_err(|err| format!("Failed to write {} to backup: {}", name, err))?;
+    }
+    zip.finish()
+        .map_err(|err| format!("Failed to finish backup: {}", err))?;
+
+    Ok(manifest)
+}
+
+/// Reads a backup archive without changing anything, so the user can see
+/// what it holds before restoring.
+pub fn inspect(path: &str) -> Result<BackupManifest, String> {
+    read_archive(path).map(|contents| contents.manifest)
+}
+
+/// Restores an archive made by `export`, all or nothing. The whole archive
+/// is read and the result worked out before anything is written. Files are
+/// then replaced one by one, each atomically, and put back if a later write
+/// or the database transaction fails.
+///
+/// On success `settings` and `granger` hold what was saved, for the caller
+/// to broadcast.
+pub fn restore(
+    path: &str,
+    mode: RestoreMode,
+    settings: &mut AppSettings,
+    granger: &mut BTreeMap<String, GrangerAnimal>,
+) -> Result<RestoreReport, String> {
+    let contents = read_archive(path)?;
+
+    let mut restored_settings = None;
+    if let (RestoreMode::Replace, Some(mut archived)) = (mode, contents.settings) {
+        archived.schema_version = SETTINGS_SCHEMA_VERSION;
+        restored_settings = Some(archived);
+    }
+    let trait_weights = &restored_settings.as_ref().unwrap_or(settings).trait_weights;
+
+    let mut herd = granger.clone();
+    let mut granger_report = ImportReport::default();
+    if let Some(animals) = contents.granger {
+        match mode {
+            RestoreMode::Merge => {
+                granger_report = granger_exchange::merge_all(&mut herd, animals, trait_weights);
+            }
+            RestoreMode::Replace => {
+                granger_report.added = animals.len();
+                herd = animals
+                    .into_iter()
+                    .map(|animal| (animal.id.clone(), animal))
+                    .collect();
+            }
+        }
+    }
+
+    let mut user_layers = BTreeMap::new();
+    if let RestoreMode::Replace = mode {
+        // Maps missing from the archive had no layers when it was made.
+        for map_id in database::user_layer_map_ids()? {
+            if !contents.user_layers.contains_key(&map_id) {
+                user_layers.insert(map_id, Vec::new());
+            }
+        }
+    }
+    for (map_id, layers) in &contents.user_layers {
+        let layers = match mode {
+            RestoreMode::Replace => layers.clone(),
+            RestoreMode::Merge => {
+                let mut merged = database::user_layers(map_id)?;
+                for layer in layers {
+                    if !merged.iter().any(|existing| existing.name == layer.name) {
+                        merged.push(layer.clone());
+                    }
+                }
+                merged
+            }
+        };
+        user_layers.insert(map_id.clone(), layers);
+    }
+
+    // New contents per file, or `None` to remove it.
+    let mut file_changes: Vec<(String, Option<String>)> = Vec::new();
+    if let Some(restored) = &restored_settings {
+        file_changes.push((SETTINGS_FILE_NAME.to_string(), Some(to_json(restored)?)));
+    }
+    for (file_name, archived) in &contents.data_files {
+        let value = match mode {
+            RestoreMode::Replace => archived.clone(),
+            RestoreMode::Merge => match persistence::load::<Value>(file_name)? {
+                Some(local) => merge_data_file(local, archived),
+                None => archived.clone(),
+            },
+        };
+        file_changes.push((file_name.clone(), Some(to_json(&value)?)));
+    }
+    let mut removed_files = 0;
+    if let RestoreMode::Replace = mode {
+        for file_name in data_file_names() {
+            if !contents.data_files.contains_key(&file_name) {
+                file_changes.push((file_name, None));
+                removed_files += 1;
+            }
+        }
+    }
+
+    let written = write_files(&file_changes)?;
+    let animals: Vec<GrangerAnimal> = herd.values().cloned().collect();
+    if let Err(err) = database::write_restore(&animals, &user_layers) {
+        roll_back(&written);
+        return Err(err);
+    }
+
+    let settings_restored = restored_settings.is_some();
+    if let Some(restored) = restored_settings {
+        *settings = restored;
+    }
+    *granger = herd;
+
+    Ok(RestoreReport {
+        mode,
+        created_at: contents.manifest.created_at,
+        settings_restored,
+        granger: granger_report,
+        user_layer_maps: contents.user_layers.len(),
+        data_files: contents.data_files.len(),
+        removed_files,
+    })
+}
+
+// A file as it was before the restore touched it; `None` if it was absent.
+struct FileSnapshot {
+    file_name: String,
+    previous: Option<String>,
+}
+
+// Applies each change atomically, undoing the earlier ones if one fails.
+// Returns snapshots for undoing them all later.
+fn write_files(changes: &[(String, Option<String>)]) -> Result<Vec<FileSnapshot>, String> {
+    let mut snapshots = Vec::new();
+    for (file_name, _) in changes {
+        snapshots.push(FileSnapshot {
+            file_name: file_name.clone(),
+            previous: persistence::read_raw(file_name)?,
+        });
+    }
+
+    for (index, (file_name, contents)) in changes.iter().enumerate() {
+        let result = match contents {
+            Some(contents) => persistence::write_raw(file_name, contents).map(|_| ()),
+            None => persistence::remove(file_name),
+        };
+        if let Err(err) = result {
+            roll_back(&snapshots[..index]);
+            return Err(err);
+        }
+    }
+    Ok(snapshots)
+}
+
+fn roll_back(snapshots: &[FileSnapshot]) {
+    for snapshot in snapshots.iter().rev() {
+        let result = match &snapshot.previous {
+            Some(previous) => persistence::write_raw(&snapshot.file_name, previous).map(|_| ()),
+            None => persistence::remove(&snapshot.file_name),
+        };
+        if let Err(err) = result {
+            warn!(
+                "Failed to put back {} after a failed restore: {}",
+                snapshot.file_name, err
+            );
+        }
+    }
+}
+
+// Lists gain the archived records they lack. Anything else, such as a
+// community cache, keeps the local copy.
+fn merge_data_file(local: Value, archived: &Value) -> Value {
+    match (local, archived) {
+        (Value::Array(mut records), Value::Array(incoming)) => {
+            for record in incoming {
+                if !records.contains(record) {
+                    records.push(record.clone());
+                }
+            }
+            Value::Array(records)
+        }
+        (local, _) => local,
+    }
+}
+
+fn read_archive(path: &str) -> Result<BackupContents, String> {
+    if !Path::new(path).is_file() {
+        return Err(format!("Backup not found: {}", path));
+    }
+    let file = File::open(path).map_err(|err| format!("Failed to open backup: {}", err))?;
+    let mut zip =
+        ZipArchive::new(file).map_err(|err| format!("Not a wurm-panels backup: {}", err))?;
+
+    let manifest: BackupManifest = serde_json::from_str(&read_entry(&mut zip, MANIFEST_NAME)?)
+        .map_err(|err| format!("Backup manifest is invalid: {}", err))?;
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(format!(
+            "Backup was made by a newer version (format {}, this version reads up to {})",
+            manifest.format_version, BACKUP_FORMAT_VERSION
+        ));
+    }
+
+    let mut contents = BackupContents {
+        manifest: manifest.clone(),
+        settings: None,
+        granger: None,
+        user_layers: BTreeMap::new(),
+        data_files: BTreeMap::new(),
+    };
+    for entry in &manifest.entries {
+        let raw = read_entry(&mut zip, &entry.path)?;
+        let invalid = |err: String| format!("Backup entry {} is invalid: {}", entry.path, err);
+        match entry.kind {
+            BackupEntryKind::Settings => {
+                let (settings, _) = migrate_settings(&raw).map_err(invalid)?;
+                contents.settings = Some(settings);
+            }
+            BackupEntryKind::Granger => {
+                let animals = serde_json::from_str(&raw).map_err(|err| invalid(err.to_string()))?;
+                contents.granger = Some(animals);
+            }
+            BackupEntryKind::UserLayers => {
+                let map_id = entry
+                    .path
+                    .strip_prefix(USER_LAYERS_DIR)
+                    .and_then(|name| name.strip_suffix(".json"))
+                    .filter(|map_id| !map_id.is_empty() && !map_id.contains(['/', '\\']))
+                    .ok_or_else(|| invalid("unexpected path".to_string()))?;
+                let layers = serde_json::from_str(&raw).map_err(|err| invalid(err.to_string()))?;
+                contents.user_layers.insert(map_id.to_string(), layers);
+            }
+            BackupEntryKind::DataFile => {
+                let file_name = entry
+                    .path
+                    .strip_prefix(DATA_DIR)
+                    .filter(|name| is_data_file(name))
+                    .ok_or_else(|| invalid("unexpected path".to_string()))?;
+                let value = serde_json::from_str(&raw).map_err(|err| invalid(err.to_string()))?;
+                contents.data_files.insert(file_name.to_string(), value);
+            }
+        }
+    }
+    Ok(contents)
+}
+
+fn read_entry(zip: &mut ZipArchive<File>, name: &str) -> Result<String, String> {
+    let mut entry = zip
+        .by_name(name)
+        .map_err(|err| format!("Backup is missing {}: {}", name, err))?;
+    let mut raw = String::new();
+    entry
+        .read_to_string(&mut raw)
+        .map_err(|err| format!("Failed to read {} from backup: {}", name, err))?;
+    Ok(raw)
+}
+
+fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value)
+        .map_err(|err| format!("Failed to serialize backup: {}", err))
+}
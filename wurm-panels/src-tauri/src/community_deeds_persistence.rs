@@ -1,6 +1,6 @@
+use crate::persistence;
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
+use tracing::{debug, info, warn};
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -65,12 +65,6 @@ pub struct CommunityMapObjectsCache {
     pub fetched_at: i64, // Unix timestamp
 }
 
-fn config_dir_path() -> Result<PathBuf, String> {
-    directories::ProjectDirs::from("com", "WefNET", "wurm-sales")
-        .map(|dirs| dirs.config_dir().to_path_buf())
-        .ok_or_else(|| "Unable to resolve configuration directory".to_string())
-}
-
 fn get_deeds_file_name(map_id: &str) -> String {
     format!("community_deeds_{}.json", map_id)
 }
@@ -93,82 +87,59 @@ fn get_map_objects_file_name(map_id: &str) -> String {
 
 #[tauri::command]
 pub fn load_community_deeds(map_id: String) -> Result<Option<CommunityDeedsCache>, String> {
-    match config_dir_path() {
-        Ok(config_dir) => {
-            let file_name = get_deeds_file_name(&map_id);
-            let deeds_path = config_dir.join(&file_name);
-            if deeds_path.exists() {
-                let raw = fs::read_to_string(&deeds_path)
-                    .map_err(|e| format!("Failed to read community_deeds.json: {}", e))?;
-
-                if raw.trim().is_empty() {
-                    return Ok(None);
-                }
+    let file_name = get_deeds_file_name(&map_id);
+    let raw = match persistence::read_raw(&file_name)? {
+        Some(raw) => raw,
+        None => return Ok(None),
+    };
 
-                let cache: Result<CommunityDeedsCache, _> = serde_json::from_str(&raw);
-                match cache {
-                    Ok(cache) => {
-                        // Check if cache is older than 24 hours
-                        let now = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs() as i64;
-                        let cache_age_seconds = now - cache.fetched_at;
-                        let one_day_seconds = 24 * 60 * 60;
-
-                        if cache_age_seconds > one_day_seconds {
-                            println!("Community deeds cache expired (age: {} seconds), will refetch", cache_age_seconds);
-                            Ok(None)
-                        } else {
-                            Ok(Some(cache))
-                        }
-                    }
-                    Err(_) => {
-                        // Try to parse as old format (just array of deeds)
-                        println!("Attempting to parse as legacy format");
-                        let deeds: Vec<CommunityDeed> = serde_json::from_str(&raw)
-                            .map_err(|e| format!("Failed to deserialize community deeds for map '{}': {}", map_id, e))?;
-                        
-                        // Wrap in cache with current timestamp
-                        let now = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs() as i64;
-                        
-                        let cache = CommunityDeedsCache {
-                            deeds,
-                            fetched_at: now,
-                        };
-                        
-                        // Save in new format
-                        let serialized = serde_json::to_string_pretty(&cache)
-                            .map_err(|e| format!("Failed to serialize updated cache: {}", e))?;
-                        fs::write(&deeds_path, serialized)
-                            .map_err(|e| format!("Failed to write updated cache: {}", e))?;
-                        
-                        println!("Converted legacy cache to new format");
-                        Ok(Some(cache))
-                    }
-                }
-            } else {
+    let cache: Result<CommunityDeedsCache, _> = serde_json::from_str(&raw);
+    match cache {
+        Ok(cache) => {
+            // Check if cache is older than 24 hours
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as i64;
+            let cache_age_seconds = now - cache.fetched_at;
+            let one_day_seconds = 24 * 60 * 60;
+
+            if cache_age_seconds > one_day_seconds {
+                info!("Community deeds cache expired (age: {} seconds), will refetch", cache_age_seconds);
                 Ok(None)
+            } else {
+                Ok(Some(cache))
             }
         }
-        Err(e) => Err(e),
+        Err(_) => {
+            // Try to parse as old format (just array of deeds)
+            debug!("Attempting to parse as legacy format");
+            let deeds: Vec<CommunityDeed> = serde_json::from_str(&raw)
+                .map_err(|e| format!("Failed to deserialize community deeds for map '{}': {}", map_id, e))?;
+            
+            // Wrap in cache with current timestamp
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as i64;
+            
+            let cache = CommunityDeedsCache {
+                deeds,
+                fetched_at: now,
+            };
+            
+            // Save in new format
+            persistence::save(&file_name, &cache)?;
+            
+            info!("Converted legacy cache to new format");
+            Ok(Some(cache))
+        }
     }
 }
 
 #[tauri::command]
 pub fn save_community_deeds(map_id: String, deeds: Vec<CommunityDeed>) -> Result<(), String> {
-    let config_dir = config_dir_path()?;
-
-    if !config_dir.exists() {
-        fs::create_dir_all(&config_dir)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-    }
-
     let file_name = get_deeds_file_name(&map_id);
-    let deeds_path = config_dir.join(&file_name);
 
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -180,13 +151,9 @@ pub fn save_community_deeds(map_id: String, deeds: Vec<CommunityDeed>) -> Result
         fetched_at: now,
     };
 
-    let serialized = serde_json::to_string_pretty(&cache)
-        .map_err(|e| format!("Failed to serialize community deeds: {}", e))?;
+    let deeds_path = persistence::save(&file_name, &cache)?;
 
-    fs::write(&deeds_path, serialized)
-        .map_err(|e| format!("Failed to write community_deeds.json: {}", e))?;
-
-    println!("Community deeds saved to {:?}", deeds_path);
+    info!("Community deeds saved to {:?}", deeds_path);
     Ok(())
 }
 
@@ -231,80 +198,56 @@ pub fn save_community_map_objects(map_id: String, objects: Vec<CommunityMapObjec
 }
 
 fn load_community_structures(map_id: &str, file_name: &str) -> Result<Option<CommunityStructuresCache>, String> {
-    match config_dir_path() {
-        Ok(config_dir) => {
-            let structures_path = config_dir.join(file_name);
-            if structures_path.exists() {
-                let raw = fs::read_to_string(&structures_path)
-                    .map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
-
-                if raw.trim().is_empty() {
-                    return Ok(None);
-                }
+    let raw = match persistence::read_raw(file_name)? {
+        Some(raw) => raw,
+        None => return Ok(None),
+    };
 
-                let cache: Result<CommunityStructuresCache, _> = serde_json::from_str(&raw);
-                match cache {
-                    Ok(cache) => {
-                        // Check if cache is older than 24 hours
-                        let now = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs() as i64;
-                        let cache_age_seconds = now - cache.fetched_at;
-                        let one_day_seconds = 24 * 60 * 60;
-
-                        if cache_age_seconds > one_day_seconds {
-                            println!("Community structures cache expired (age: {} seconds), will refetch", cache_age_seconds);
-                            Ok(None)
-                        } else {
-                            Ok(Some(cache))
-                        }
-                    }
-                    Err(_) => {
-                        // Try to parse as old format (just array of structures)
-                        println!("Attempting to parse as legacy format");
-                        let structures: Vec<CommunityStructure> = serde_json::from_str(&raw)
-                            .map_err(|e| format!("Failed to deserialize community structures for map '{}': {}", map_id, e))?;
-
-                        // Wrap in cache with current timestamp
-                        let now = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs() as i64;
-
-                        let cache = CommunityStructuresCache {
-                            structures,
-                            fetched_at: now,
-                        };
-
-                        // Save in new format
-                        let serialized = serde_json::to_string_pretty(&cache)
-                            .map_err(|e| format!("Failed to serialize updated cache: {}", e))?;
-                        fs::write(&structures_path, serialized)
-                            .map_err(|e| format!("Failed to write updated cache: {}", e))?;
-
-                        println!("Converted legacy cache to new format");
-                        Ok(Some(cache))
-                    }
-                }
-            } else {
+    let cache: Result<CommunityStructuresCache, _> = serde_json::from_str(&raw);
+    match cache {
+        Ok(cache) => {
+            // Check if cache is older than 24 hours
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as i64;
+            let cache_age_seconds = now - cache.fetched_at;
+            let one_day_seconds = 24 * 60 * 60;
+
+            if cache_age_seconds > one_day_seconds {
+                info!("Community structures cache expired (age: {} seconds), will refetch", cache_age_seconds);
                 Ok(None)
+            } else {
+                Ok(Some(cache))
             }
         }
-        Err(e) => Err(e),
-    }
-}
+        Err(_) => {
+            // Try to parse as old format (just array of structures)
+            debug!("Attempting to parse as legacy format");
+            let structures: Vec<CommunityStructure> = serde_json::from_str(&raw)
+                .map_err(|e| format!("Failed to deserialize community structures for map '{}': {}", map_id, e))?;
 
-fn save_community_structures(_map_id: &str, structures: Vec<CommunityStructure>, file_name: &str) -> Result<(), String> {
-    let config_dir = config_dir_path()?;
+            // Wrap in cache with current timestamp
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as i64;
 
-    if !config_dir.exists() {
-        fs::create_dir_all(&config_dir)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-    }
+            let cache = CommunityStructuresCache {
+                structures,
+                fetched_at: now,
+            };
+
+            // Save in new format
+            persistence::save(file_name, &cache)?;
 
-    let structures_path = config_dir.join(file_name);
+            info!("Converted legacy cache to new format");
+            Ok(Some(cache))
+        }
+    }
+}
 
+fn save_community_structures(_map_id: &str, structures: Vec<CommunityStructure>, file_name: &str) -> Result<(), String> {
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
@@ -315,108 +258,80 @@ fn save_community_structures(_map_id: &str, structures: Vec<CommunityStructure>,
         fetched_at: now,
     };
 
-    let serialized = serde_json::to_string_pretty(&cache)
-        .map_err(|e| format!("Failed to serialize community structures: {}", e))?;
-
-    fs::write(&structures_path, serialized)
-        .map_err(|e| format!("Failed to write {}: {}", file_name, e))?;
+    let structures_path = persistence::save(file_name, &cache)?;
 
-    println!("Community structures saved to {:?}", structures_path);
+    info!("Community structures saved to {:?}", structures_path);
     Ok(())
 }
 
 fn load_community_bridges_cache(_map_id: &str, file_name: &str) -> Result<Option<CommunityBridgesCache>, String> {
-    match config_dir_path() {
-        Ok(config_dir) => {
-            let bridges_path = config_dir.join(file_name);
-            if bridges_path.exists() {
-                let raw = fs::read_to_string(&bridges_path)
-                    .map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
-
-                if raw.trim().is_empty() {
-                    return Ok(None);
-                }
+    let raw = match persistence::read_raw(file_name)? {
+        Some(raw) => raw,
+        None => return Ok(None),
+    };
 
-                let cache: Result<CommunityBridgesCache, _> = serde_json::from_str(&raw);
-                match cache {
-                    Ok(cache) => {
-                        // Check if cache is older than 24 hours
-                        let now = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs() as i64;
-                        let cache_age_seconds = now - cache.fetched_at;
-                        let one_day_seconds = 24 * 60 * 60;
-
-                        if cache_age_seconds > one_day_seconds {
-                            println!("Community bridges cache expired (age: {} seconds), will refetch", cache_age_seconds);
-                            Ok(None)
-                        } else {
-                            Ok(Some(cache))
-                        }
-                    }
-                    Err(e1) => {
-                        // Try to parse as old format (just array of bridges)
-                        println!("Attempting to parse as legacy format");
-                        let legacy_result: Result<Vec<CommunityBridge>, _> = serde_json::from_str(&raw);
-                        match legacy_result {
-                            Ok(bridges) => {
-                                // Wrap in cache with current timestamp
-                                let now = std::time::SystemTime::now()
-                                    .duration_since(std::time::UNIX_EPOCH)
-                                    .unwrap()
-                                    .as_secs() as i64;
-
-                                let cache = CommunityBridgesCache {
-                                    bridges,
-                                    fetched_at: now,
-                                };
-
-                                // Save in new format
-                                let serialized = serde_json::to_string_pretty(&cache)
-                                    .map_err(|e| format!("Failed to serialize updated cache: {}", e))?;
-                                fs::write(&bridges_path, serialized)
-                                    .map_err(|e| format!("Failed to write updated cache: {}", e))?;
-
-                                println!("Converted legacy cache to new format");
-                                Ok(Some(cache))
-                            }
-                            Err(e2) => {
-                                // Both parsing attempts failed, log the errors and return None to trigger refetch
-                                println!("Failed to parse bridges cache as new format: {}", e1);
-                                println!("Failed to parse bridges cache as legacy format: {}", e2);
-                                println!("Cache file appears corrupted, deleting and will refetch data");
-                                
-                                // Delete the corrupted file
-                                if let Err(delete_err) = fs::remove_file(&bridges_path) {
-                                    println!("Failed to delete corrupted cache file: {}", delete_err);
-                                } else {
-                                    println!("Deleted corrupted cache file");
-                                }
-                                
-                                Ok(None)
-                            }
-                        }
+    let cache: Result<CommunityBridgesCache, _> = serde_json::from_str(&raw);
+    match cache {
+        Ok(cache) => {
+            // Check if cache is older than 24 hours
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as i64;
+            let cache_age_seconds = now - cache.fetched_at;
+            let one_day_seconds = 24 * 60 * 60;
+
+            if cache_age_seconds > one_day_seconds {
+                info!("Community bridges cache expired (age: {} seconds), will refetch", cache_age_seconds);
+                Ok(None)
+            } else {
+                Ok(Some(cache))
+            }
+        }
+        Err(e1) => {
+            // Try to parse as old format (just array of bridges)
+            debug!("Attempting to parse as legacy format");
+            let legacy_result: Result<Vec<CommunityBridge>, _> = serde_json::from_str(&raw);
+            match legacy_result {
+                Ok(bridges) => {
+                    // Wrap in cache with current timestamp
+                    let now = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap()
+                        .as_secs() as i64;
+
+                    let cache = CommunityBridgesCache {
+                        bridges,
+                        fetched_at: now,
+                    };
+
+                    // Save in new format
+                    persistence::save(file_name, &cache)?;
+
+                    info!("Converted legacy cache to new format");
+                    Ok(Some(cache))
+                }
+                Err(e2) => {
+                    // Both parsing attempts failed, log the errors and return None to trigger refetch
+                    warn!("Failed to parse bridges cache as new format: {}", e1);
+                    warn!("Failed to parse bridges cache as legacy format: {}", e2);
+                    warn!("Cache file appears corrupted, deleting and will refetch data");
+                    
+                    // Delete the corrupted file
+                    if let Err(delete_err) = persistence::remove(file_name) {
+                        warn!("Failed to delete corrupted cache file: {}", delete_err);
+                    } else {
+                        info!("Deleted corrupted cache file");
                     }
+                    
+                    Ok(None)
                 }
-            } else {
-                Ok(None)
             }
         }
-        Err(e) => Err(e),
     }
 }
 
 fn save_community_bridges_cache(_map_id: &str, bridges: Vec<CommunityBridge>, file_name: &str) -> Result<(), String> {
-    let config_dir = config_dir_path()?;
-
-    if !config_dir.exists() {
-        fs::create_dir_all(&config_dir)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-    }
-
-    let bridges_path = config_dir.join(file_name);
-
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
@@ -427,108 +342,80 @@ fn save_community_bridges_cache(_map_id: &str, bridges: Vec<CommunityBridge>, fi
         fetched_at: now,
     };
 
-    let serialized = serde_json::to_string_pretty(&cache)
-        .map_err(|e| format!("Failed to serialize community bridges: {}", e))?;
+    let bridges_path = persistence::save(file_name, &cache)?;
 
-    fs::write(&bridges_path, serialized)
-        .map_err(|e| format!("Failed to write {}: {}", file_name, e))?;
-
-    println!("Community bridges saved to {:?}", bridges_path);
+    info!("Community bridges saved to {:?}", bridges_path);
     Ok(())
 }
 
 fn load_community_map_objects_cache(_map_id: &str, file_name: &str) -> Result<Option<CommunityMapObjectsCache>, String> {
-    match config_dir_path() {
-        Ok(config_dir) => {
-            let objects_path = config_dir.join(file_name);
-            if objects_path.exists() {
-                let raw = fs::read_to_string(&objects_path)
-                    .map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
-
-                if raw.trim().is_empty() {
-                    return Ok(None);
-                }
+    let raw = match persistence::read_raw(file_name)? {
+        Some(raw) => raw,
+        None => return Ok(None),
+    };
 
-                let cache: Result<CommunityMapObjectsCache, _> = serde_json::from_str(&raw);
-                match cache {
-                    Ok(cache) => {
-                        // Check if cache is older than 24 hours
-                        let now = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs() as i64;
-                        let cache_age_seconds = now - cache.fetched_at;
-                        let one_day_seconds = 24 * 60 * 60;
-
-                        if cache_age_seconds > one_day_seconds {
-                            println!("Community map objects cache expired (age: {} seconds), will refetch", cache_age_seconds);
-                            Ok(None)
-                        } else {
-                            Ok(Some(cache))
-                        }
-                    }
-                    Err(e1) => {
-                        // Try to parse as old format (just array of objects)
-                        println!("Attempting to parse as legacy format");
-                        let legacy_result: Result<Vec<CommunityMapObject>, _> = serde_json::from_str(&raw);
-                        match legacy_result {
-                            Ok(objects) => {
-                                // Wrap in cache with current timestamp
-                                let now = std::time::SystemTime::now()
-                                    .duration_since(std::time::UNIX_EPOCH)
-                                    .unwrap()
-                                    .as_secs() as i64;
-
-                                let cache = CommunityMapObjectsCache {
-                                    objects,
-                                    fetched_at: now,
-                                };
-
-                                // Save in new format
-                                let serialized = serde_json::to_string_pretty(&cache)
-                                    .map_err(|e| format!("Failed to serialize updated cache: {}", e))?;
-                                fs::write(&objects_path, serialized)
-                                    .map_err(|e| format!("Failed to write updated cache: {}", e))?;
-
-                                println!("Converted legacy cache to new format");
-                                Ok(Some(cache))
-                            }
-                            Err(e2) => {
-                                // Both parsing attempts failed, log the errors and return None to trigger refetch
-                                println!("Failed to parse map objects cache as new format: {}", e1);
-                                println!("Failed to parse map objects cache as legacy format: {}", e2);
-                                println!("Cache file appears corrupted, deleting and will refetch data");
-                                
-                                // Delete the corrupted file
-                                if let Err(delete_err) = fs::remove_file(&objects_path) {
-                                    println!("Failed to delete corrupted cache file: {}", delete_err);
-                                } else {
-                                    println!("Deleted corrupted cache file");
-                                }
-                                
-                                Ok(None)
-                            }
-                        }
+    let cache: Result<CommunityMapObjectsCache, _> = serde_json::from_str(&raw);
+    match cache {
+        Ok(cache) => {
+            // Check if cache is older than 24 hours
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as i64;
+            let cache_age_seconds = now - cache.fetched_at;
+            let one_day_seconds = 24 * 60 * 60;
+
+            if cache_age_seconds > one_day_seconds {
+                info!("Community map objects cache expired (age: {} seconds), will refetch", cache_age_seconds);
+                Ok(None)
+            } else {
+                Ok(Some(cache))
+            }
+        }
+        Err(e1) => {
+            // Try to parse as old format (just array of objects)
+            debug!("Attempting to parse as legacy format");
+            let legacy_result: Result<Vec<CommunityMapObject>, _> = serde_json::from_str(&raw);
+            match legacy_result {
+                Ok(objects) => {
+                    // Wrap in cache with current timestamp
+                    let now = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap()
+                        .as_secs() as i64;
+
+                    let cache = CommunityMapObjectsCache {
+                        objects,
+                        fetched_at: now,
+                    };
+
+                    // Save in new format
+                    persistence::save(file_name, &cache)?;
+
+                    info!("Converted legacy cache to new format");
+                    Ok(Some(cache))
+                }
+                Err(e2) => {
+                    // Both parsing attempts failed, log the errors and return None to trigger refetch
+                    warn!("Failed to parse map objects cache as new format: {}", e1);
+                    warn!("Failed to parse map objects cache as legacy format: {}", e2);
+                    warn!("Cache file appears corrupted, deleting and will refetch data");
+                    
+                    // Delete the corrupted file
+                    if let Err(delete_err) = persistence::remove(file_name) {
+                        warn!("Failed to delete corrupted cache file: {}", delete_err);
+                    } else {
+                        info!("Deleted corrupted cache file");
                     }
+                    
+                    Ok(None)
                 }
-            } else {
-                Ok(None)
             }
         }
-        Err(e) => Err(e),
     }
 }
 
 fn save_community_map_objects_cache(_map_id: &str, objects: Vec<CommunityMapObject>, file_name: &str) -> Result<(), String> {
-    let config_dir = config_dir_path()?;
-
-    if !config_dir.exists() {
-        fs::create_dir_all(&config_dir)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-    }
-
-    let objects_path = config_dir.join(file_name);
-
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
@@ -539,13 +426,9 @@ fn save_community_map_objects_cache(_map_id: &str, objects: Vec<CommunityMapObje
         fetched_at: now,
     };
 
-    let serialized = serde_json::to_string_pretty(&cache)
-        .map_err(|e| format!("Failed to serialize community map objects: {}", e))?;
-
-    fs::write(&objects_path, serialized)
-        .map_err(|e| format!("Failed to write {}: {}", file_name, e))?;
+    let objects_path = persistence::save(file_name, &cache)?;
 
-    println!("Community map objects saved to {:?}", objects_path);
+    info!("Community map objects saved to {:?}", objects_path);
     Ok(())
 }
 
@@ -577,7 +460,7 @@ pub fn fetch_community_deeds(url: String) -> Result<Vec<CommunityDeed>, String>
         .as_array()
         .ok_or("Invalid data structure")?;
 
-    println!("Total values: {}", values.len());
+    debug!("Total values: {}", values.len());
 
     let deeds: Vec<CommunityDeed> = values.iter()
         .enumerate()
@@ -585,37 +468,37 @@ pub fn fetch_community_deeds(url: String) -> Result<Vec<CommunityDeed>, String>
             let arr = match row.as_array() {
                 Some(a) => a,
                 None => {
-                    println!("Row {} is not an array: {:?}", i, row);
+                    debug!("Row {} is not an array: {:?}", i, row);
                     return None;
                 }
             };
             if arr.len() < 3 { 
-                println!("Skipping row {}: len {}", i, arr.len());
+                debug!("Skipping row {}: len {}", i, arr.len());
                 return None;
             }
             // Skip if first row looks like headers
             if i == 0 && arr[0].as_str() == Some("Name") {
-                println!("Skipping header row");
+                debug!("Skipping header row");
                 return None;
             }
             let name = match arr[0].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} name not string: {:?}", i, arr[0]);
+                    debug!("Row {} name not string: {:?}", i, arr[0]);
                     return None;
                 }
             };
             let x_str = match arr[1].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} x not string: {:?}", i, arr[1]);
+                    debug!("Row {} x not string: {:?}", i, arr[1]);
                     return None;
                 }
             };
             let y_str = match arr[2].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} y not string: {:?}", i, arr[2]);
+                    debug!("Row {} y not string: {:?}", i, arr[2]);
                     return None;
                 }
             };
@@ -623,14 +506,14 @@ pub fn fetch_community_deeds(url: String) -> Result<Vec<CommunityDeed>, String>
             let x = match x_str.parse() {
                 Ok(n) => n,
                 Err(_) => {
-                    println!("Row {} x parse fail: {}", i, x_str);
+                    debug!("Row {} x parse fail: {}", i, x_str);
                     return None;
                 }
             };
             let y = match y_str.parse() {
                 Ok(n) => n,
                 Err(_) => {
-                    println!("Row {} y parse fail: {}", i, y_str);
+                    debug!("Row {} y parse fail: {}", i, y_str);
                     return None;
                 }
             };
@@ -643,7 +526,7 @@ pub fn fetch_community_deeds(url: String) -> Result<Vec<CommunityDeed>, String>
         })
         .collect();
 
-    println!("Collected deeds: {}", deeds.len());
+    debug!("Collected deeds: {}", deeds.len());
 
     Ok(deeds)
 }
@@ -683,34 +566,34 @@ pub fn fetch_community_bridges(url: String) -> Result<Vec<CommunityBridge>, Stri
 
     // Debug: Log all valueRanges
     if let Some(value_ranges) = data["valueRanges"].as_array() {
-        println!("Available valueRanges count: {}", value_ranges.len());
+        debug!("Available valueRanges count: {}", value_ranges.len());
         for (i, vr) in value_ranges.iter().enumerate() {
             if let Some(values) = vr["values"].as_array() {
-                println!("valueRanges[{}]: {} rows", i, values.len());
+                debug!("valueRanges[{}]: {} rows", i, values.len());
             }
         }
     }
 
     // Extract values from valueRanges[2] (bridges) - try different indices if not found
     let values = if let Some(v) = data["valueRanges"][2]["values"].as_array() {
-        println!("Found bridges data in valueRanges[2]");
+        debug!("Found bridges data in valueRanges[2]");
         v
     } else if let Some(v) = data["valueRanges"][1]["values"].as_array() {
-        println!("Found bridges data in valueRanges[1]");
+        debug!("Found bridges data in valueRanges[1]");
         v
     } else if let Some(v) = data["valueRanges"][3]["values"].as_array() {
-        println!("Found bridges data in valueRanges[3]");
+        debug!("Found bridges data in valueRanges[3]");
         v
     } else if let Some(v) = data["valueRanges"][4]["values"].as_array() {
-        println!("Found bridges data in valueRanges[4]");
+        debug!("Found bridges data in valueRanges[4]");
         v
     } else {
-        println!("No bridges data found in any valueRanges, returning empty array");
+        info!("No bridges data found in any valueRanges, returning empty array");
         return Ok(vec![]);
     };
 
-    println!("Total bridges values: {}", values.len());
-    println!("First few bridge rows: {:?}", &values.iter().take(3).collect::<Vec<_>>());
+    debug!("Total bridges values: {}", values.len());
+    debug!("First few bridge rows: {:?}", &values.iter().take(3).collect::<Vec<_>>());
 
     let bridges: Vec<CommunityBridge> = values.iter()
         .enumerate()
@@ -718,51 +601,51 @@ pub fn fetch_community_bridges(url: String) -> Result<Vec<CommunityBridge>, Stri
             let arr = match row.as_array() {
                 Some(a) => a,
                 None => {
-                    println!("Row {} is not an array: {:?}", i, row);
+                    debug!("Row {} is not an array: {:?}", i, row);
                     return None;
                 }
             };
             if arr.len() < 5 {
-                println!("Skipping row {}: len {}", i, arr.len());
+                debug!("Skipping row {}: len {}", i, arr.len());
                 return None;
             }
             // Skip if first row looks like headers
             if i == 0 && arr[0].as_str() == Some("Name") {
-                println!("Skipping header row");
+                debug!("Skipping header row");
                 return None;
             }
             let name = match arr[0].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} name not string: {:?}", i, arr[0]);
+                    debug!("Row {} name not string: {:?}", i, arr[0]);
                     return None;
                 }
             };
             let x1_str = match arr[1].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} x1 not string: {:?}", i, arr[1]);
+                    debug!("Row {} x1 not string: {:?}", i, arr[1]);
                     return None;
                 }
             };
             let y1_str = match arr[2].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} y1 not string: {:?}", i, arr[2]);
+                    debug!("Row {} y1 not string: {:?}", i, arr[2]);
                     return None;
                 }
             };
             let x2_str = match arr[3].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} x2 not string: {:?}", i, arr[3]);
+                    debug!("Row {} x2 not string: {:?}", i, arr[3]);
                     return None;
                 }
             };
             let y2_str = match arr[4].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} y2 not string: {:?}", i, arr[4]);
+                    debug!("Row {} y2 not string: {:?}", i, arr[4]);
                     return None;
                 }
             };
@@ -770,28 +653,28 @@ pub fn fetch_community_bridges(url: String) -> Result<Vec<CommunityBridge>, Stri
             let x1 = match x1_str.parse() {
                 Ok(n) => n,
                 Err(_) => {
-                    println!("Row {} x1 parse fail: {}", i, x1_str);
+                    debug!("Row {} x1 parse fail: {}", i, x1_str);
                     return None;
                 }
             };
             let y1 = match y1_str.parse() {
                 Ok(n) => n,
                 Err(_) => {
-                    println!("Row {} y1 parse fail: {}", i, y1_str);
+                    debug!("Row {} y1 parse fail: {}", i, y1_str);
                     return None;
                 }
             };
             let x2 = match x2_str.parse() {
                 Ok(n) => n,
                 Err(_) => {
-                    println!("Row {} x2 parse fail: {}", i, x2_str);
+                    debug!("Row {} x2 parse fail: {}", i, x2_str);
                     return None;
                 }
             };
             let y2 = match y2_str.parse() {
                 Ok(n) => n,
                 Err(_) => {
-                    println!("Row {} y2 parse fail: {}", i, y2_str);
+                    debug!("Row {} y2 parse fail: {}", i, y2_str);
                     return None;
                 }
             };
@@ -802,7 +685,7 @@ pub fn fetch_community_bridges(url: String) -> Result<Vec<CommunityBridge>, Stri
         })
         .collect();
 
-    println!("Collected bridges: {}", bridges.len());
+    debug!("Collected bridges: {}", bridges.len());
 
     Ok(bridges)
 }
@@ -832,10 +715,10 @@ pub fn fetch_community_map_objects(url: String) -> Result<Vec<CommunityMapObject
 
     // Debug: Log all valueRanges
     if let Some(value_ranges) = data["valueRanges"].as_array() {
-        println!("Available valueRanges count: {}", value_ranges.len());
+        debug!("Available valueRanges count: {}", value_ranges.len());
         for (i, vr) in value_ranges.iter().enumerate() {
             if let Some(values) = vr["values"].as_array() {
-                println!("valueRanges[{}]: {} rows", i, values.len());
+                debug!("valueRanges[{}]: {} rows", i, values.len());
             }
         }
     }
@@ -845,8 +728,8 @@ pub fn fetch_community_map_objects(url: String) -> Result<Vec<CommunityMapObject
         .as_array()
         .ok_or("Invalid data structure - valueRanges[3] not found")?;
 
-    println!("Total map objects values: {}", values.len());
-    println!("First few map object rows: {:?}", &values.iter().take(3).collect::<Vec<_>>());
+    debug!("Total map objects values: {}", values.len());
+    debug!("First few map object rows: {:?}", &values.iter().take(3).collect::<Vec<_>>());
 
     let objects: Vec<CommunityMapObject> = values.iter()
         .enumerate()
@@ -854,51 +737,51 @@ pub fn fetch_community_map_objects(url: String) -> Result<Vec<CommunityMapObject
             let arr = match row.as_array() {
                 Some(a) => a,
                 None => {
-                    println!("Row {} is not an array: {:?}", i, row);
+                    debug!("Row {} is not an array: {:?}", i, row);
                     return None;
                 }
             };
             if arr.len() < 8 {
-                println!("Skipping row {}: len {}", i, arr.len());
+                debug!("Skipping row {}: len {}", i, arr.len());
                 return None;
             }
             // Skip if first row looks like headers
             if i == 0 && arr[0].as_str() == Some("Name") {
-                println!("Skipping header row");
+                debug!("Skipping header row");
                 return None;
             }
             let name = match arr[0].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} name not string: {:?}", i, arr[0]);
+                    debug!("Row {} name not string: {:?}", i, arr[0]);
                     return None;
                 }
             };
             let x1_str = match arr[1].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} x1 not string: {:?}", i, arr[1]);
+                    debug!("Row {} x1 not string: {:?}", i, arr[1]);
                     return None;
                 }
             };
             let y1_str = match arr[2].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} y1 not string: {:?}", i, arr[2]);
+                    debug!("Row {} y1 not string: {:?}", i, arr[2]);
                     return None;
                 }
             };
             let x2_str = match arr[3].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} x2 not string: {:?}", i, arr[3]);
+                    debug!("Row {} x2 not string: {:?}", i, arr[3]);
                     return None;
                 }
             };
             let y2_str = match arr[4].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} y2 not string: {:?}", i, arr[4]);
+                    debug!("Row {} y2 not string: {:?}", i, arr[4]);
                     return None;
                 }
             };
@@ -910,28 +793,28 @@ pub fn fetch_community_map_objects(url: String) -> Result<Vec<CommunityMapObject
             let x1 = match x1_str.parse() {
                 Ok(n) => n,
                 Err(_) => {
-                    println!("Row {} x1 parse fail: {}", i, x1_str);
+                    debug!("Row {} x1 parse fail: {}", i, x1_str);
                     return None;
                 }
             };
             let y1 = match y1_str.parse() {
                 Ok(n) => n,
                 Err(_) => {
-                    println!("Row {} y1 parse fail: {}", i, y1_str);
+                    debug!("Row {} y1 parse fail: {}", i, y1_str);
                     return None;
                 }
             };
             let x2 = match x2_str.parse() {
                 Ok(n) => n,
                 Err(_) => {
-                    println!("Row {} x2 parse fail: {}", i, x2_str);
+                    debug!("Row {} x2 parse fail: {}", i, x2_str);
                     return None;
                 }
             };
             let y2 = match y2_str.parse() {
                 Ok(n) => n,
                 Err(_) => {
-                    println!("Row {} y2 parse fail: {}", i, y2_str);
+                    debug!("Row {} y2 parse fail: {}", i, y2_str);
                     return None;
                 }
             };
@@ -945,7 +828,7 @@ pub fn fetch_community_map_objects(url: String) -> Result<Vec<CommunityMapObject
         })
         .collect();
 
-    println!("Collected map objects: {}", objects.len());
+    debug!("Collected map objects: {}", objects.len());
 
     Ok(objects)
 }
@@ -977,7 +860,7 @@ fn fetch_community_structures(url: String, structure_type_filter: &str) -> Resul
         .as_array()
         .ok_or("Invalid data structure - valueRanges[5] not found")?;
 
-    println!("Total structures values: {}", values.len());
+    debug!("Total structures values: {}", values.len());
 
     let structures: Vec<CommunityStructure> = values.iter()
         .enumerate()
@@ -985,44 +868,44 @@ fn fetch_community_structures(url: String, structure_type_filter: &str) -> Resul
             let arr = match row.as_array() {
                 Some(a) => a,
                 None => {
-                    println!("Row {} is not an array: {:?}", i, row);
+                    debug!("Row {} is not an array: {:?}", i, row);
                     return None;
                 }
             };
             if arr.len() < 4 {
-                println!("Skipping row {}: len {}", i, arr.len());
+                debug!("Skipping row {}: len {}", i, arr.len());
                 return None;
             }
             // Skip if first row looks like headers
             if i == 0 && arr[0].as_str() == Some("Name") {
-                println!("Skipping header row");
+                debug!("Skipping header row");
                 return None;
             }
             let name = match arr[0].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} name not string: {:?}", i, arr[0]);
+                    debug!("Row {} name not string: {:?}", i, arr[0]);
                     return None;
                 }
             };
             let x_str = match arr[1].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} x not string: {:?}", i, arr[1]);
+                    debug!("Row {} x not string: {:?}", i, arr[1]);
                     return None;
                 }
             };
             let y_str = match arr[2].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} y not string: {:?}", i, arr[2]);
+                    debug!("Row {} y not string: {:?}", i, arr[2]);
                     return None;
                 }
             };
             let structure_type = match arr[3].as_str() {
                 Some(s) => s,
                 None => {
-                    println!("Row {} structure_type not string: {:?}", i, arr[3]);
+                    debug!("Row {} structure_type not string: {:?}", i, arr[3]);
                     return None;
                 }
             };
@@ -1035,14 +918,14 @@ fn fetch_community_structures(url: String, structure_type_filter: &str) -> Resul
             let x = match x_str.parse() {
                 Ok(n) => n,
                 Err(_) => {
-                    println!("Row {} x parse fail: {}", i, x_str);
+                    debug!("Row {} x parse fail: {}", i, x_str);
                     return None;
                 }
             };
             let y = match y_str.parse() {
                 Ok(n) => n,
                 Err(_) => {
-                    println!("Row {} y parse fail: {}", i, y_str);
+                    debug!("Row {} y parse fail: {}", i, y_str);
                     return None;
                 }
             };
@@ -1054,7 +937,7 @@ fn fetch_community_structures(url: String, structure_type_filter: &str) -> Resul
         })
         .collect();
 
-    println!("Collected {} structures of type {}: {}", structures.len(), structure_type_filter, structures.len());
+    debug!("Collected {} structures of type {}: {}", structures.len(), structure_type_filter, structures.len());
 
     Ok(structures)
 }
\ No newline at end of file
@@ -0,0 +1,287 @@
+use crate::granger::{unix_now, GrangerAnimal};
+use crate::persistence;
+use crate::user_layers_persistence::UserLayer;
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tracing::{info, warn};
+
+pub const DATABASE_FILE_NAME: &str = "wurm-panels.db";
+
+// Only the granger herd and user map layers live here so far. Price history,
+// merchant sales and the community caches are still JSON files written
+// through `persistence`.
+
+// JSON stores that predate the database, imported once on first open.
+const LEGACY_GRANGER_FILE_NAME: &str = "granger.json";
+const LEGACY_USER_LAYERS_PREFIX: &str = "user_layers_";
+
+/// Schema changes, applied in order on open. `PRAGMA user_version` records
+/// how many have run. A tracker that needs storage appends an entry that
+/// creates its tables; existing entries are never edited.
+const MIGRATIONS: &[&str] = &[
+    // 1: granger herd, user map layers and the legacy import log.
+    "CREATE TABLE legacy_imports (
+         file_name TEXT PRIMARY KEY,
+         imported_at INTEGER NOT NULL
+     );
+     CREATE TABLE granger_animals (
+         id TEXT PRIMARY KEY,
+         name TEXT NOT NULL,
+         species TEXT,
+         settlement TEXT,
+         caretaker TEXT,
+         archived INTEGER NOT NULL DEFAULT 0,
+         updated_at TEXT NOT NULL,
+         data TEXT NOT NULL
+     );
+     CREATE INDEX granger_animals_species ON granger_animals (species);
+     CREATE INDEX granger_animals_settlement ON granger_animals (settlement);
+     CREATE TABLE user_layers (
+         map_id TEXT NOT NULL,
+         position INTEGER NOT NULL,
+         name TEXT NOT NULL,
+         data TEXT NOT NULL,
+         PRIMARY KEY (map_id, position)
+     );",
+];
+
+static CONNECTION: OnceLock<Result<Mutex<Connection>, String>> = OnceLock::new();
+
+/// Runs `action` against the shared connection, opening and migrating the
+/// database on first use.
+pub fn with_connection<T>(
+    action: impl FnOnce(&mut Connection) -> rusqlite::Result<T>,
+) -> Result<T, String> {
+    let connection = CONNECTION
+        .get_or_init(|| open().map(Mutex::new))
+        .as_ref()
+        .map_err(|err| err.clone())?;
+    let mut connection = connection
+        .lock()
+        .map_err(|err| format!("Failed to lock database: {}", err))?;
+    action(&mut connection).map_err(|err| format!("Database error: {}", err))
+}
+
+fn open() -> Result<Connection, String> {
+    let path = persistence::data_path(DATABASE_FILE_NAME)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|err| format!("Failed to create config directory: {}", err))?;
+    }
+
+    let mut connection =
+        Connection::open(&path).map_err(|err| format!("Failed to open database: {}", err))?;
+    connection
+        .busy_timeout(Duration::from_secs(5))
+        .and_then(|_| connection.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(())))
+        .map_err(|err| format!("Failed to configure database: {}", err))?;
+    migrate(&mut connection).map_err(|err| format!("Failed to migrate database: {}", err))?;
+    import_legacy_files(&mut connection);
+
+    info!("Opened database at {:?}", path);
+    Ok(connection)
+}
+
+fn migrate(connection: &mut Connection) -> rusqlite::Result<()> {
+    let version: i64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let tx = connection.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", (index + 1) as i64)?;
+        tx.commit()?;
+        info!("Migrated database to schema {}", index + 1);
+    }
+    Ok(())
+}
+
+// Copies each JSON store written before the database existed. A file is
+// imported once and then left alone, so it doubles as a backup.
+fn import_legacy_files(connection: &mut Connection) {
+    let mut file_names = vec![LEGACY_GRANGER_FILE_NAME.to_string()];
+    if let Ok(entries) = persistence::config_dir_path().and_then(|dir| {
+        fs::read_dir(dir).map_err(|err| format!("Failed to list config directory: {}", err))
+    }) {
+        file_names.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| {
+                    name.starts_with(LEGACY_USER_LAYERS_PREFIX) && name.ends_with(".json")
+                }),
+        );
+    }
+
+    for file_name in file_names {
+        let result = connection
+            .transaction()
+            .map_err(|err| err.to_string())
+            .and_then(|tx| {
+                let imported = import_legacy_file(&tx, &file_name)?;
+                tx.commit().map_err(|err| err.to_string())?;
+                Ok(imported)
+            });
+        match result {
+            Ok(Some(count)) => info!("Imported {} records from {}", count, file_name),
+            Ok(None) => {}
+            Err(err) => warn!("Failed to import {}: {}", file_name, err),
+        }
+    }
+}
+
+// Returns how many records were imported, or `None` if there was nothing to do.
+fn import_legacy_file(tx: &Transaction, file_name: &str) -> Result<Option<usize>, String> {
+    let already_imported = tx
+        .query_row(
+            "SELECT 1 FROM legacy_imports WHERE file_name = ?1",
+            [file_name],
+            |_| Ok(()),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?
+        .is_some();
+    if already_imported {
+        return Ok(None);
+    }
+
+    let count = if file_name == LEGACY_GRANGER_FILE_NAME {
+        let Some(animals) = persistence::load::<Vec<GrangerAnimal>>(file_name)? else {
+            return Ok(None);
+        };
+        for animal in &animals {
+            insert_granger_animal(tx, animal).map_err(|err| err.to_string())?;
+        }
+        animals.len()
+    } else {
+        let map_id = file_name
+            .trim_start_matches(LEGACY_USER_LAYERS_PREFIX)
+            .trim_end_matches(".json");
+        let layers = persistence::load::<Vec<UserLayer>>(file_name)?.unwrap_or_default();
+        write_user_layers(tx, map_id, &layers).map_err(|err| err.to_string())?;
+        layers.len()
+    };
+
+    tx.execute(
+        "INSERT INTO legacy_imports (file_name, imported_at) VALUES (?1, ?2)",
+        params![file_name, unix_now()],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(Some(count))
+}
+
+/// Every animal in the herd, archived ones included.
+pub fn granger_animals() -> Result<Vec<GrangerAnimal>, String> {
+    with_connection(|connection| {
+        let mut statement = connection.prepare("SELECT data FROM granger_animals ORDER BY id")?;
+        let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+        rows.map(|raw| from_json(&raw?)).collect()
+    })
+}
+
+/// Writes `animals` over their stored versions and deletes `removed`, in one
+/// transaction.
+pub fn save_granger_changes(animals: &[&GrangerAnimal], removed: &[&str]) -> Result<(), String> {
+    with_connection(|connection| {
+        let tx = connection.transaction()?;
+        for id in removed {
+            tx.execute("DELETE FROM granger_animals WHERE id = ?1", [id])?;
+        }
+        for animal in animals {
+            insert_granger_animal(&tx, animal)?;
+        }
+        tx.commit()
+    })
+}
+
+fn insert_granger_animal(tx: &Transaction, animal: &GrangerAnimal) -> rusqlite::Result<()> {
+    tx.execute(
+        "INSERT OR REPLACE INTO granger_animals
+             (id, name, species, settlement, caretaker, archived, updated_at, data)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            animal.id,
+            animal.name,
+            animal.species,
+            animal.settlement,
+            animal.caretaker,
+            animal.archived,
+            animal.updated_at,
+            to_json(animal)?,
+        ],
+    )?;
+    Ok(())
+}
+
+/// The user's drawn layers for one map, in display order.
+pub fn user_layers(map_id: &str) -> Result<Vec<UserLayer>, String> {
+    with_connection(|connection| {
+        let mut statement = connection
+            .prepare("SELECT data FROM user_layers WHERE map_id = ?1 ORDER BY position")?;
+        let rows = statement.query_map([map_id], |row| row.get::<_, String>(0))?;
+        rows.map(|raw| from_json(&raw?)).collect()
+    })
+}
+
+/// Map ids that have any user layers stored.
+pub fn user_layer_map_ids() -> Result<Vec<String>, String> {
+    with_connection(|connection| {
+        let mut statement =
+            connection.prepare("SELECT DISTINCT map_id FROM user_layers ORDER BY map_id")?;
+        let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+        rows.collect()
+    })
+}
+
+/// Replaces the layers stored for one map.
+pub fn replace_user_layers(map_id: &str, layers: &[UserLayer]) -> Result<(), String> {
+    with_connection(|connection| {
+        let tx = connection.transaction()?;
+        write_user_layers(&tx, map_id, layers)?;
+        tx.commit()
+    })
+}
+
+/// Writes a restored herd and the user layers of the given maps in one
+/// transaction, so a failure leaves the database as it was.
+pub fn write_restore(
+    animals: &[GrangerAnimal],
+    user_layers: &BTreeMap<String, Vec<UserLayer>>,
+) -> Result<(), String> {
+    with_connection(|connection| {
+        let tx = connection.transaction()?;
+        tx.execute("DELETE FROM granger_animals", [])?;
+        for animal in animals {
+            insert_granger_animal(&tx, animal)?;
+        }
+        for (map_id, layers) in user_layers {
+            write_user_layers(&tx, map_id, layers)?;
+        }
+        tx.commit()
+    })
+}
+
+fn write_user_layers(tx: &Transaction, map_id: &str, layers: &[UserLayer]) -> rusqlite::Result<()> {
+    tx.execute("DELETE FROM user_layers WHERE map_id = ?1", [map_id])?;
+    for (position, layer) in layers.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO user_layers (map_id, position, name, data) VALUES (?1, ?2, ?3, ?4)",
+            params![map_id, position as i64, layer.name, to_json(layer)?],
+        )?;
+    }
+    Ok(())
+}
+
+fn to_json<T: Serialize>(value: &T) -> rusqlite::Result<String> {
+    serde_json::to_string(value)
+        .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))
+}
+
+fn from_json<T: DeserializeOwned>(raw: &str) -> rusqlite::Result<T> {
+    serde_json::from_str(raw)
+        .map_err(|err| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(err)))
+}
@@ -1,11 +1,17 @@
-use directories::ProjectDirs;
-use serde::{Deserialize, Serialize};
+use crate::database;
+use crate::granger_births::expected_birth_at;
+use crate::granger_history::{push_examination, snapshot_of, ExamineSnapshot};
+use crate::granger_traits::{
+    breeding_score, classify_sentence, label as trait_label, CreatureTrait, TraitWeights,
+};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::BTreeMap;
-use std::fs;
-use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tracing::warn;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct GrangerAnimal {
     pub id: String,
     pub name: String,
@@ -13,18 +19,116 @@ pub struct GrangerAnimal {
     pub age: Option<String>,
     pub custom_label: Option<String>,
     pub species: Option<String>,
+    /// "male" or "female", read from the He/She examine lines.
+    #[serde(default)]
+    pub sex: Option<String>,
     pub settlement: Option<String>,
     pub caretaker: Option<String>,
     pub condition: Option<String>,
     pub traits: Vec<String>,
+    #[serde(default)]
+    pub canonical_traits: Vec<CreatureTrait>,
+    /// Trait sentences the catalogue does not know, kept for review.
+    #[serde(default)]
+    pub unrecognised_traits: Vec<String>,
+    #[serde(default)]
+    pub breeding_score: f64,
     pub trait_points: Option<u32>,
     pub colour: Option<String>,
+    #[serde(default)]
+    pub pregnant: bool,
+    /// Due text as printed, e.g. "about 3 days".
+    #[serde(default)]
+    pub birth_due: Option<String>,
+    #[serde(default)]
+    pub due_in_days: Option<f64>,
+    #[serde(default)]
+    pub expected_birth_at: Option<i64>, // Unix timestamp
+    /// Set once the birth reminder for this pregnancy has been shown.
+    #[serde(default)]
+    pub birth_notified: bool,
+    #[serde(default)]
+    pub branded: bool,
+    #[serde(default)]
+    pub brand_owner: Option<String>,
+    #[serde(default)]
+    pub mother: Option<String>,
+    #[serde(default)]
+    pub father: Option<String>,
+    #[serde(default)]
+    pub genesis: Option<String>,
+    /// Status lines beyond the first condition line.
+    #[serde(default)]
+    pub status_lines: Vec<String>,
+    /// Examine lines no parser recognised, kept for later reprocessing.
+    #[serde(default)]
+    pub raw_lines: Vec<String>,
     pub updated_at: String,
+    /// Ids of stored animals this record could not be told apart from.
+    /// Cleared by merging into one of them or splitting it off as distinct.
+    #[serde(default)]
+    pub ambiguous_with: Vec<String>,
+    /// Ids of stored animals the user has confirmed are different animals,
+    /// by splitting one off from the other.
+    #[serde(default)]
+    pub distinct_from: Vec<String>,
+    /// Every examine of this animal, oldest first.
+    #[serde(default)]
+    pub examinations: Vec<ExamineSnapshot>,
+    /// Sold or dead animals are archived rather than deleted, keeping their
+    /// pedigree, but are left out of breeding plans.
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Fields corrected by hand, named as in `GrangerAnimalEdit`. Later
+    /// examines leave them alone.
+    #[serde(default)]
+    pub overridden_fields: Vec<String>,
 }
 
-pub type SharedGrangerEntries = Arc<Mutex<BTreeMap<String, GrangerAnimal>>>;
+/// Corrections to a parsed record. Absent fields are left alone and an empty
+/// string clears the field; `trait_points` is cleared with `null`.
+///
+/// Name and species also decide which examines match the record. After
+/// either is changed, an examine still showing the old value no longer
+/// matches and is stored as a separate animal.
+#[derive(Clone, Default, Deserialize)]
+pub struct GrangerAnimalEdit {
+    pub name: Option<String>,
+    pub age: Option<String>,
+    pub custom_label: Option<String>,
+    pub species: Option<String>,
+    pub sex: Option<String>,
+    pub settlement: Option<String>,
+    pub caretaker: Option<String>,
+    pub colour: Option<String>,
+    pub mother: Option<String>,
+    pub father: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    pub trait_points: Option<Option<u32>>,
+    pub canonical_traits: Option<Vec<CreatureTrait>>,
+}
+
+// Tells a field sent as `null` (clear it) apart from one left out.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
 
-const GRANGER_FILE_NAME: &str = "granger.json";
+/// Outcome of matching a fresh examine against the stored herd.
+pub enum IdentityMatch {
+    Existing(String),
+    New,
+    Ambiguous(Vec<String>),
+}
+
+pub type SharedGrangerEntries = Arc<Mutex<BTreeMap<String, GrangerAnimal>>>;
 
 pub fn new_store_with(initial: BTreeMap<String, GrangerAnimal>) -> SharedGrangerEntries {
     Arc::new(Mutex::new(initial))
@@ -34,58 +138,603 @@ pub fn to_vec(entries: &BTreeMap<String, GrangerAnimal>) -> Vec<GrangerAnimal> {
     entries.values().cloned().collect()
 }
 
-pub fn load_from_disk() -> BTreeMap<String, GrangerAnimal> {
-    match config_dir_path() {
-        Ok(dir) => {
-            let path = dir.join(GRANGER_FILE_NAME);
-            match fs::read_to_string(&path) {
-                Ok(raw) => match serde_json::from_str::<Vec<GrangerAnimal>>(&raw) {
-                    Ok(items) => items
-                        .into_iter()
-                        .map(|animal| (animal.id.clone(), animal))
-                        .collect(),
-                    Err(err) => {
-                        println!("Failed to deserialize granger data: {}", err);
-                        BTreeMap::new()
-                    }
-                },
-                Err(err) => {
-                    if err.kind() != std::io::ErrorKind::NotFound {
-                        println!("Failed to read granger data: {}", err);
-                    }
-                    BTreeMap::new()
-                }
+/// Finds which stored animal an examine belongs to.
+///
+/// Animals share a name freely, so a stored record only matches when none of
+/// its stable traits (species, sex, colour, custom label and parents)
+/// disagree with the examine. A trait the record has but the examine lacks
+/// counts as a disagreement, unless it was set by hand.
+///
+/// Records kept on another settlement or branded for another owner are set
+/// aside: the examine only matches them when no other record does, and then
+/// it is reported as ambiguous, since it may be a namesake on another deed or
+/// the same animal moved. More than one matching record is ambiguous too,
+/// unless the user has split them apart and one shares more known traits
+/// with the examine than the others.
+pub fn resolve_identity(
+    entries: &BTreeMap<String, GrangerAnimal>,
+    animal: &GrangerAnimal,
+) -> IdentityMatch {
+    let (candidates, elsewhere): (Vec<&GrangerAnimal>, Vec<&GrangerAnimal>) = entries
+        .values()
+        .filter(|existing| existing.name.eq_ignore_ascii_case(&animal.name))
+        .filter(|existing| is_compatible(existing, animal))
+        .partition(|existing| same_place(existing, animal));
+    let candidates: Vec<String> = candidates
+        .into_iter()
+        .map(|existing| existing.id.clone())
+        .collect();
+    if candidates.is_empty() && !elsewhere.is_empty() {
+        return IdentityMatch::Ambiguous(
+            elsewhere
+                .into_iter()
+                .map(|existing| existing.id.clone())
+                .collect(),
+        );
+    }
+
+    // An examine still waiting to be merged or split absorbs repeat examines,
+    // rather than each one adding another unresolved copy.
+    let pending = candidates.iter().find(|id| {
+        entries
+            .get(*id)
+            .is_some_and(|existing| !existing.ambiguous_with.is_empty())
+    });
+
+    match (candidates.len(), pending) {
+        (0, _) => IdentityMatch::New,
+        (1, _) => IdentityMatch::Existing(candidates[0].clone()),
+        (_, Some(id)) => IdentityMatch::Existing(id.clone()),
+        _ => match split_candidate(entries, &candidates, animal) {
+            Some(id) => IdentityMatch::Existing(id),
+            None => IdentityMatch::Ambiguous(candidates),
+        },
+    }
+}
+
+// Among candidates the user has confirmed are all different animals, the one
+// sharing the most known stable traits with the examine, if it is unique.
+fn split_candidate(
+    entries: &BTreeMap<String, GrangerAnimal>,
+    candidates: &[String],
+    animal: &GrangerAnimal,
+) -> Option<String> {
+    let records: Vec<&GrangerAnimal> = candidates.iter().filter_map(|id| entries.get(id)).collect();
+    let all_split = records.iter().all(|record| {
+        candidates
+            .iter()
+            .all(|other| *other == record.id || record.distinct_from.contains(other))
+    });
+    if !all_split {
+        return None;
+    }
+
+    let mut scored: Vec<(usize, &GrangerAnimal)> = records
+        .into_iter()
+        .map(|record| (shared_traits(record, animal), record))
+        .collect();
+    scored.sort_by_key(|(shared, _)| std::cmp::Reverse(*shared));
+    match scored.as_slice() {
+        [(best, record), (runner_up, _), ..] if best > runner_up => Some(record.id.clone()),
+        _ => None,
+    }
+}
+
+// Traits that stay with an animal for life, named as in `GrangerAnimalEdit`.
+fn stable_traits(animal: &GrangerAnimal) -> [(&'static str, &Option<String>); 6] {
+    [
+        ("species", &animal.species),
+        ("sex", &animal.sex),
+        ("colour", &animal.colour),
+        ("custom_label", &animal.custom_label),
+        ("mother", &animal.mother),
+        ("father", &animal.father),
+    ]
+}
+
+/// Whether none of the stable traits of `existing` disagree with `animal`.
+/// A trait only `existing` has counts as a disagreement unless set by hand.
+pub fn is_compatible(existing: &GrangerAnimal, animal: &GrangerAnimal) -> bool {
+    stable_traits(existing)
+        .into_iter()
+        .zip(stable_traits(animal))
+        .all(
+            |((field, stored), (_, examined))| match (stored, examined) {
+                (Some(stored), Some(examined)) => stored.eq_ignore_ascii_case(examined),
+                (Some(_), None) => existing.overridden_fields.iter().any(|name| name == field),
+                (None, _) => true,
+            },
+        )
+}
+
+// Whether the record could be kept where the examine was made: neither the
+// settlement nor the brand owner is known on both and different.
+fn same_place(existing: &GrangerAnimal, animal: &GrangerAnimal) -> bool {
+    [
+        (&existing.settlement, &animal.settlement),
+        (&existing.brand_owner, &animal.brand_owner),
+    ]
+    .into_iter()
+    .all(|(stored, examined)| match (stored, examined) {
+        (Some(stored), Some(examined)) => stored.eq_ignore_ascii_case(examined),
+        _ => true,
+    })
+}
+
+// How many stable traits are known on both records (and so agree).
+fn shared_traits(existing: &GrangerAnimal, animal: &GrangerAnimal) -> usize {
+    stable_traits(existing)
+        .into_iter()
+        .zip(stable_traits(animal))
+        .filter(|((_, stored), (_, examined))| stored.is_some() && examined.is_some())
+        .count()
+}
+
+/// Stores a freshly examined animal, updating the record it resolves to or
+/// adding a new one. Returns the id the examine was stored under.
+pub fn record_examine(
+    entries: &mut BTreeMap<String, GrangerAnimal>,
+    mut animal: GrangerAnimal,
+) -> String {
+    let examined_at = unix_now();
+    let snapshot = snapshot_of(&animal, examined_at);
+    animal.expected_birth_at = expected_birth_at(&animal, examined_at);
+
+    let id = match resolve_identity(entries, &animal) {
+        IdentityMatch::Existing(id) => {
+            if let Some(existing) = entries.remove(&id) {
+                keep_overrides(&mut animal, &existing);
+                animal.ambiguous_with = existing.ambiguous_with;
+                animal.distinct_from = existing.distinct_from;
+                animal.examinations = existing.examinations;
+                animal.archived = existing.archived;
+                animal.tags = existing.tags;
+                animal.notes = existing.notes;
+                // A re-examine of the same pregnancy should not notify twice.
+                animal.birth_notified = existing.birth_notified
+                    && match (existing.expected_birth_at, animal.expected_birth_at) {
+                        (Some(before), Some(after)) => (before - after).abs() < 86_400,
+                        _ => false,
+                    };
             }
+            id
         }
-        Err(err) => {
-            println!(
-                "Failed to resolve config directory for granger data: {}",
+        IdentityMatch::New => new_animal_id(entries, &animal.name),
+        IdentityMatch::Ambiguous(candidates) => {
+            animal.ambiguous_with = candidates;
+            new_animal_id(entries, &animal.name)
+        }
+    };
+
+    animal.id = id.clone();
+    push_examination(&mut animal.examinations, snapshot);
+    entries.insert(id.clone(), animal);
+    id
+}
+
+// Puts back the fields the user corrected by hand over what the examine read.
+fn keep_overrides(animal: &mut GrangerAnimal, existing: &GrangerAnimal) {
+    for field in &existing.overridden_fields {
+        match field.as_str() {
+            "name" => animal.name = existing.name.clone(),
+            "age" => animal.age = existing.age.clone(),
+            "custom_label" => animal.custom_label = existing.custom_label.clone(),
+            "species" => animal.species = existing.species.clone(),
+            "sex" => animal.sex = existing.sex.clone(),
+            "settlement" => animal.settlement = existing.settlement.clone(),
+            "caretaker" => animal.caretaker = existing.caretaker.clone(),
+            "colour" => animal.colour = existing.colour.clone(),
+            "mother" => animal.mother = existing.mother.clone(),
+            "father" => animal.father = existing.father.clone(),
+            "trait_points" => animal.trait_points = existing.trait_points,
+            "canonical_traits" => {
+                animal.traits = existing.traits.clone();
+                animal.canonical_traits = existing.canonical_traits.clone();
+                animal.unrecognised_traits = existing.unrecognised_traits.clone();
+                animal.breeding_score = existing.breeding_score;
+            }
+            _ => {}
+        }
+    }
+    animal.overridden_fields = existing.overridden_fields.clone();
+}
+
+/// Folds `source_id` into `target_id`. Fields known on the source replace the
+/// target's, and the source record is removed.
+pub fn merge_animals(
+    entries: &mut BTreeMap<String, GrangerAnimal>,
+    source_id: &str,
+    target_id: &str,
+) -> Result<(), String> {
+    if source_id == target_id {
+        return Err("Cannot merge an animal into itself".to_string());
+    }
+    if !entries.contains_key(target_id) {
+        return Err(format!("Animal not found: {}", target_id));
+    }
+    let source = entries
+        .remove(source_id)
+        .ok_or_else(|| format!("Animal not found: {}", source_id))?;
+
+    if let Some(target) = entries.get_mut(target_id) {
+        target.name = source.name;
+        if !source.descriptors.is_empty() {
+            target.descriptors = source.descriptors;
+        }
+        if !source.traits.is_empty() {
+            target.traits = source.traits;
+            target.canonical_traits = source.canonical_traits;
+            target.unrecognised_traits = source.unrecognised_traits;
+            target.breeding_score = source.breeding_score;
+        }
+        target.age = source.age.or(target.age.take());
+        target.custom_label = source.custom_label.or(target.custom_label.take());
+        target.species = source.species.or(target.species.take());
+        target.sex = source.sex.or(target.sex.take());
+        target.settlement = source.settlement.or(target.settlement.take());
+        target.caretaker = source.caretaker.or(target.caretaker.take());
+        target.condition = source.condition.or(target.condition.take());
+        target.trait_points = source.trait_points.or(target.trait_points);
+        target.colour = source.colour.or(target.colour.take());
+        target.pregnant = source.pregnant;
+        target.birth_due = source.birth_due;
+        target.due_in_days = source.due_in_days;
+        target.expected_birth_at = source.expected_birth_at;
+        target.birth_notified = source.birth_notified;
+        target.branded = source.branded || target.branded;
+        target.brand_owner = source.brand_owner.or(target.brand_owner.take());
+        target.mother = source.mother.or(target.mother.take());
+        target.father = source.father.or(target.father.take());
+        target.genesis = source.genesis.or(target.genesis.take());
+        target.status_lines = source.status_lines;
+        target.raw_lines = source.raw_lines;
+        target.updated_at = source.updated_at;
+        for snapshot in source.examinations {
+            push_examination(&mut target.examinations, snapshot);
+        }
+        for tag in source.tags {
+            if !target.tags.contains(&tag) {
+                target.tags.push(tag);
+            }
+        }
+        target.notes = match (target.notes.take(), source.notes) {
+            (Some(own), Some(other)) if own != other => Some(format!("{}\n{}", own, other)),
+            (own, other) => own.or(other),
+        };
+        for field in source.overridden_fields {
+            if !target.overridden_fields.contains(&field) {
+                target.overridden_fields.push(field);
+            }
+        }
+        for other in source.distinct_from {
+            if other != target_id && !target.distinct_from.contains(&other) {
+                target.distinct_from.push(other);
+            }
+        }
+    }
+
+    forget_ambiguity(entries, source_id);
+    // Whatever was distinct from the source is distinct from what it became.
+    for animal in entries.values_mut() {
+        if !animal.distinct_from.iter().any(|other| other == source_id) {
+            continue;
+        }
+        animal.distinct_from.retain(|other| other != source_id);
+        if animal.id != target_id && !animal.distinct_from.iter().any(|other| other == target_id) {
+            animal.distinct_from.push(target_id.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Confirms `id` as an animal in its own right, clearing any ambiguity with
+/// the records it was confused with. The pairs are remembered as distinct,
+/// so later examines are not confused between them again.
+pub fn split_animal(entries: &mut BTreeMap<String, GrangerAnimal>, id: &str) -> Result<(), String> {
+    let animal = entries
+        .get_mut(id)
+        .ok_or_else(|| format!("Animal not found: {}", id))?;
+    let others = std::mem::take(&mut animal.ambiguous_with);
+    for other in &others {
+        if !animal.distinct_from.contains(other) {
+            animal.distinct_from.push(other.clone());
+        }
+    }
+    for other in &others {
+        if let Some(record) = entries.get_mut(other) {
+            if !record.distinct_from.iter().any(|known| known == id) {
+                record.distinct_from.push(id.to_string());
+            }
+        }
+    }
+    forget_ambiguity(entries, id);
+    Ok(())
+}
+
+pub fn delete_animal(
+    entries: &mut BTreeMap<String, GrangerAnimal>,
+    id: &str,
+) -> Result<GrangerAnimal, String> {
+    let removed = entries
+        .remove(id)
+        .ok_or_else(|| format!("Animal not found: {}", id))?;
+    forget_ambiguity(entries, id);
+    for animal in entries.values_mut() {
+        animal.distinct_from.retain(|other| other != id);
+    }
+    Ok(removed)
+}
+
+pub fn set_archived(
+    entries: &mut BTreeMap<String, GrangerAnimal>,
+    id: &str,
+    archived: bool,
+) -> Result<(), String> {
+    let animal = entries
+        .get_mut(id)
+        .ok_or_else(|| format!("Animal not found: {}", id))?;
+    animal.archived = archived;
+    Ok(())
+}
+
+/// Applies hand corrections and marks the edited fields as overridden.
+pub fn edit_animal(
+    entries: &mut BTreeMap<String, GrangerAnimal>,
+    id: &str,
+    edit: GrangerAnimalEdit,
+    weights: &TraitWeights,
+) -> Result<(), String> {
+    let animal = entries
+        .get_mut(id)
+        .ok_or_else(|| format!("Animal not found: {}", id))?;
+    let mut edited: Vec<&str> = Vec::new();
+
+    if let Some(name) = edit.name {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Animal name cannot be empty".to_string());
+        }
+        animal.name = name.to_string();
+        edited.push("name");
+    }
+
+    let text_fields = [
+        ("age", edit.age, &mut animal.age),
+        ("custom_label", edit.custom_label, &mut animal.custom_label),
+        ("species", edit.species, &mut animal.species),
+        ("settlement", edit.settlement, &mut animal.settlement),
+        ("caretaker", edit.caretaker, &mut animal.caretaker),
+        ("colour", edit.colour, &mut animal.colour),
+        ("mother", edit.mother, &mut animal.mother),
+        ("father", edit.father, &mut animal.father),
+    ];
+    for (field_name, value, field) in text_fields {
+        if let Some(value) = value {
+            *field = non_empty(&value);
+            edited.push(field_name);
+        }
+    }
+
+    if let Some(sex) = edit.sex {
+        animal.sex = match non_empty(&sex).map(|sex| sex.to_ascii_lowercase()) {
+            Some(sex) if sex == "male" || sex == "female" => Some(sex),
+            Some(other) => return Err(format!("Unknown sex: {}", other)),
+            None => None,
+        };
+        edited.push("sex");
+    }
+
+    if let Some(points) = edit.trait_points {
+        animal.trait_points = points;
+        edited.push("trait_points");
+    }
+
+    if let Some(edited_traits) = edit.canonical_traits {
+        let mut traits: Vec<CreatureTrait> = Vec::new();
+        for creature_trait in edited_traits {
+            if !traits.contains(&creature_trait) {
+                traits.push(creature_trait);
+            }
+        }
+        animal.traits = traits
+            .iter()
+            .map(|creature_trait| trait_label(*creature_trait).to_string())
+            .collect();
+        animal.canonical_traits = traits;
+        animal.breeding_score = breeding_score(&animal.canonical_traits, weights);
+        edited.push("canonical_traits");
+    }
+
+    for field in edited {
+        if !animal.overridden_fields.iter().any(|known| known == field) {
+            animal.overridden_fields.push(field.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Replaces an animal's tags, dropping blanks and duplicates.
+pub fn set_tags(
+    entries: &mut BTreeMap<String, GrangerAnimal>,
+    id: &str,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    let animal = entries
+        .get_mut(id)
+        .ok_or_else(|| format!("Animal not found: {}", id))?;
+
+    let mut cleaned: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty()
+            && !cleaned
+                .iter()
+                .any(|existing| existing.eq_ignore_ascii_case(tag))
+        {
+            cleaned.push(tag.to_string());
+        }
+    }
+    animal.tags = cleaned;
+    Ok(())
+}
+
+pub fn set_notes(
+    entries: &mut BTreeMap<String, GrangerAnimal>,
+    id: &str,
+    notes: Option<String>,
+) -> Result<(), String> {
+    let animal = entries
+        .get_mut(id)
+        .ok_or_else(|| format!("Animal not found: {}", id))?;
+    animal.notes = notes.as_deref().and_then(non_empty);
+    Ok(())
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+fn forget_ambiguity(entries: &mut BTreeMap<String, GrangerAnimal>, id: &str) {
+    for animal in entries.values_mut() {
+        animal.ambiguous_with.retain(|other| other != id);
+    }
+}
+
+pub(crate) fn new_animal_id(entries: &BTreeMap<String, GrangerAnimal>, name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or(0);
+
+    let mut id = format!("{}-{}", slug, millis);
+    let mut suffix = 1;
+    while entries.contains_key(&id) {
+        id = format!("{}-{}-{}", slug, millis, suffix);
+        suffix += 1;
+    }
+    id
+}
+
+pub(crate) fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Sends the current herd to every window, as the watcher does after an examine.
+pub fn broadcast(app_handle: &AppHandle, snapshot: Vec<GrangerAnimal>) {
+    if let Err(err) = app_handle.emit("granger-entries", snapshot.clone()) {
+        warn!("Failed to emit granger entries: {:?}", err);
+    }
+    if let Err(err) = app_handle.emit_to("granger", "granger-entries", snapshot) {
+        if !matches!(err, tauri::Error::WebviewNotFound) {
+            warn!(
+                "Failed to emit granger entries to granger window: {:?}",
                 err
             );
-            BTreeMap::new()
         }
     }
 }
 
-pub fn persist(entries: &BTreeMap<String, GrangerAnimal>) -> Result<(), String> {
-    let dir = config_dir_path()?;
+/// Recomputes every animal's breeding score, e.g. after the weights change.
+pub fn rescore(entries: &mut BTreeMap<String, GrangerAnimal>, weights: &TraitWeights) {
+    for animal in entries.values_mut() {
+        animal.breeding_score = breeding_score(&animal.canonical_traits, weights);
+    }
+}
+
+// Records saved before the trait catalogue only hold the free text after "has".
+fn classify_legacy_traits(mut animal: GrangerAnimal) -> GrangerAnimal {
+    if animal.sex.is_none() {
+        animal.sex = animal.condition.as_deref().and_then(sex_from_sentence);
+    }
 
-    if !dir.exists() {
-        fs::create_dir_all(&dir)
-            .map_err(|err| format!("Failed to create config directory: {}", err))?;
+    if !animal.canonical_traits.is_empty() || animal.traits.is_empty() {
+        return animal;
     }
 
-    let path = dir.join(GRANGER_FILE_NAME);
-    let serialized = serde_json::to_string_pretty(&to_vec(entries))
-        .map_err(|err| format!("Failed to serialize granger data: {}", err))?;
+    let mut labels = Vec::new();
+    for text in std::mem::take(&mut animal.traits) {
+        match classify_sentence(&text) {
+            Some(creature_trait) => {
+                if !animal.canonical_traits.contains(&creature_trait) {
+                    animal.canonical_traits.push(creature_trait);
+                    labels.push(trait_label(creature_trait).to_string());
+                }
+            }
+            None => animal.unrecognised_traits.push(text),
+        }
+    }
+    animal.traits = labels;
+    animal
+}
 
-    fs::write(&path, serialized).map_err(|err| format!("Failed to write granger data: {}", err))?;
+pub fn sex_from_sentence(sentence: &str) -> Option<String> {
+    if sentence.starts_with("He ") {
+        Some("male".to_string())
+    } else if sentence.starts_with("She ") {
+        Some("female".to_string())
+    } else {
+        None
+    }
+}
 
-    Ok(())
+pub fn load_from_disk() -> BTreeMap<String, GrangerAnimal> {
+    match database::granger_animals() {
+        Ok(items) => items
+            .into_iter()
+            .map(classify_legacy_traits)
+            .map(|animal| (animal.id.clone(), animal))
+            .collect(),
+        Err(err) => {
+            warn!("Failed to load granger data: {}", err);
+            BTreeMap::new()
+        }
+    }
+}
+
+/// Saves only the animals with these ids. Ids no longer in the herd are
+/// deleted from storage.
+pub fn persist_animals(
+    entries: &BTreeMap<String, GrangerAnimal>,
+    ids: &[String],
+) -> Result<(), String> {
+    let mut changed = Vec::new();
+    let mut removed = Vec::new();
+    for id in ids {
+        match entries.get(id) {
+            Some(animal) => changed.push(animal),
+            None => removed.push(id.as_str()),
+        }
+    }
+    database::save_granger_changes(&changed, &removed)
 }
 
-fn config_dir_path() -> Result<PathBuf, String> {
-    ProjectDirs::from("com", "WefNET", "wurm-sales")
-        .map(|dirs| dirs.config_dir().to_path_buf())
-        .ok_or_else(|| "Unable to resolve configuration directory".to_string())
+/// Ids of animals added, changed or removed between two versions of the herd.
+pub fn changed_ids(
+    before: &BTreeMap<String, GrangerAnimal>,
+    after: &BTreeMap<String, GrangerAnimal>,
+) -> Vec<String> {
+    let removed = before.keys().filter(|id| !after.contains_key(*id));
+    let changed = after
+        .iter()
+        .filter(|(id, animal)| before.get(*id) != Some(*animal))
+        .map(|(id, _)| id);
+    removed.chain(changed).cloned().collect()
 }
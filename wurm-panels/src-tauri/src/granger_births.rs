@@ -0,0 +1,134 @@
+use crate::granger::{
+    broadcast as broadcast_granger, persist_animals as persist_granger_animals,
+    to_vec as granger_to_vec, unix_now, GrangerAnimal, SharedGrangerEntries,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+use tracing::warn;
+
+// Births expected within this many seconds trigger a desktop notification.
+const BIRTH_NOTICE_WINDOW_SECS: i64 = 12 * 60 * 60;
+
+// Births this long overdue drop off the upcoming list; the examine is stale.
+const OVERDUE_GRACE_SECS: i64 = 2 * 24 * 60 * 60;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Serialize)]
+pub struct UpcomingBirth {
+    pub id: String,
+    pub name: String,
+    pub species: Option<String>,
+    pub settlement: Option<String>,
+    pub father: Option<String>,
+    pub birth_due: Option<String>,
+    pub expected_birth_at: i64, // Unix timestamp
+    pub hours_remaining: f64,
+    pub notified: bool,
+}
+
+/// Works out when a pregnancy reported at `examined_at` should end.
+pub fn expected_birth_at(animal: &GrangerAnimal, examined_at: i64) -> Option<i64> {
+    if !animal.pregnant {
+        return None;
+    }
+    let days = animal.due_in_days?;
+    Some(examined_at + (days * 86_400.0).round() as i64)
+}
+
+/// Pregnant animals with a known due time, soonest first. `within_days`
+/// limits how far ahead to look.
+pub fn upcoming_births(
+    entries: &BTreeMap<String, GrangerAnimal>,
+    now: i64,
+    within_days: Option<f64>,
+) -> Vec<UpcomingBirth> {
+    let horizon = within_days.map(|days| now + (days * 86_400.0).round() as i64);
+
+    let mut births: Vec<UpcomingBirth> = entries
+        .values()
+        .filter(|animal| animal.pregnant && !animal.archived)
+        .filter_map(|animal| {
+            let expected = animal.expected_birth_at?;
+            if expected < now - OVERDUE_GRACE_SECS || horizon.is_some_and(|limit| expected > limit)
+            {
+                return None;
+            }
+            Some(UpcomingBirth {
+                id: animal.id.clone(),
+                name: animal.name.clone(),
+                species: animal.species.clone(),
+                settlement: animal.settlement.clone(),
+                father: animal.father.clone(),
+                birth_due: animal.birth_due.clone(),
+                expected_birth_at: expected,
+                hours_remaining: (expected - now) as f64 / 3600.0,
+                notified: animal.birth_notified,
+            })
+        })
+        .collect();
+
+    births.sort_by_key(|birth| birth.expected_birth_at);
+    births
+}
+
+/// Checks the herd once a minute and notifies about births coming up within
+/// `BIRTH_NOTICE_WINDOW_SECS`, once per pregnancy.
+pub fn start_reminders(app_handle: AppHandle, granger_entries: SharedGrangerEntries) {
+    thread::spawn(move || loop {
+        notify_due_births(&app_handle, &granger_entries);
+        thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+fn notify_due_births(app_handle: &AppHandle, granger_entries: &SharedGrangerEntries) {
+    let now = unix_now();
+    let Ok(mut entries) = granger_entries.lock() else {
+        warn!("Failed to lock granger entries for birth reminders");
+        return;
+    };
+
+    let due: Vec<UpcomingBirth> = upcoming_births(&entries, now, None)
+        .into_iter()
+        .filter(|birth| !birth.notified)
+        .filter(|birth| birth.expected_birth_at - now <= BIRTH_NOTICE_WINDOW_SECS)
+        .collect();
+    if due.is_empty() {
+        return;
+    }
+
+    for birth in &due {
+        if let Some(animal) = entries.get_mut(&birth.id) {
+            animal.birth_notified = true;
+        }
+    }
+    let due_ids: Vec<String> = due.iter().map(|birth| birth.id.clone()).collect();
+    if let Err(err) = persist_granger_animals(&entries, &due_ids) {
+        warn!("Failed to persist granger data: {}", err);
+    }
+    let snapshot = granger_to_vec(&entries);
+    drop(entries);
+
+    for birth in &due {
+        let body = if birth.hours_remaining <= 0.0 {
+            "Should have given birth by now".to_string()
+        } else {
+            format!("Due in about {:.0} hours", birth.hours_remaining.ceil())
+        };
+        if let Err(err) = app_handle
+            .notification()
+            .builder()
+            .title(format!("{} is about to give birth", birth.name))
+            .body(body)
+            .show()
+        {
+            warn!("Failed to show birth notification: {:?}", err);
+        }
+    }
+
+    broadcast_granger(app_handle, snapshot);
+}
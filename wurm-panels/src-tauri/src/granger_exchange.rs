@@ -0,0 +1,476 @@
+use crate::granger::{
+    is_compatible, new_animal_id, resolve_identity, GrangerAnimal, IdentityMatch,
+};
+use crate::granger_traits::{breeding_score, label as trait_label, CreatureTrait, TraitWeights};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+// Separator for list fields flattened into a single CSV cell.
+const LIST_SEPARATOR: &str = "; ";
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExchangeFormat {
+    Csv,
+    Json,
+}
+
+impl ExchangeFormat {
+    /// Picks the format from a file extension when the caller did not say.
+    pub fn from_path(path: &str) -> Self {
+        if path.to_ascii_lowercase().ends_with(".csv") {
+            ExchangeFormat::Csv
+        } else {
+            ExchangeFormat::Json
+        }
+    }
+}
+
+/// Narrows an export to part of the herd. Empty filters match everything.
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct GrangerFilter {
+    pub ids: Option<Vec<String>>,
+    pub species: Option<String>,
+    pub settlement: Option<String>,
+    pub caretaker: Option<String>,
+    pub sex: Option<String>,
+    pub tag: Option<String>,
+    pub include_archived: bool,
+}
+
+impl GrangerFilter {
+    fn matches(&self, animal: &GrangerAnimal) -> bool {
+        let field_matches = |wanted: &Option<String>, own: &Option<String>| match wanted {
+            Some(wanted) => own
+                .as_deref()
+                .is_some_and(|own| own.eq_ignore_ascii_case(wanted)),
+            None => true,
+        };
+
+        (self.include_archived || !animal.archived)
+            && self.ids.as_ref().is_none_or(|ids| ids.contains(&animal.id))
+            && field_matches(&self.species, &animal.species)
+            && field_matches(&self.settlement, &animal.settlement)
+            && field_matches(&self.caretaker, &animal.caretaker)
+            && field_matches(&self.sex, &animal.sex)
+            && self
+                .tag
+                .as_ref()
+                .is_none_or(|tag| animal.tags.iter().any(|own| own.eq_ignore_ascii_case(tag)))
+    }
+}
+
+/// One animal as a spreadsheet row. Columns follow `GrangerAnimal`, with list
+/// fields joined by "; ". Examine history and raw lines are not exported.
+#[derive(Serialize, Deserialize)]
+struct GrangerCsvRow {
+    id: String,
+    name: String,
+    descriptors: String,
+    age: Option<String>,
+    custom_label: Option<String>,
+    species: Option<String>,
+    sex: Option<String>,
+    settlement: Option<String>,
+    caretaker: Option<String>,
+    condition: Option<String>,
+    traits: String,
+    canonical_traits: String,
+    unrecognised_traits: String,
+    breeding_score: f64,
+    trait_points: Option<u32>,
+    colour: Option<String>,
+    pregnant: bool,
+    birth_due: Option<String>,
+    expected_birth_at: Option<i64>,
+    branded: bool,
+    brand_owner: Option<String>,
+    mother: Option<String>,
+    father: Option<String>,
+    genesis: Option<String>,
+    archived: bool,
+    tags: String,
+    notes: Option<String>,
+    updated_at: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ImportConflict {
+    pub id: String,
+    pub name: String,
+    pub field: &'static str,
+    pub existing: Option<String>,
+    pub incoming: Option<String>,
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    /// Values the file disagreed on. The stored value is kept in each case.
+    pub conflicts: Vec<ImportConflict>,
+}
+
+pub fn export(
+    entries: &BTreeMap<String, GrangerAnimal>,
+    filter: &GrangerFilter,
+    format: ExchangeFormat,
+    path: &str,
+) -> Result<usize, String> {
+    let selected: Vec<&GrangerAnimal> = entries
+        .values()
+        .filter(|animal| filter.matches(animal))
+        .collect();
+
+    match format {
+        ExchangeFormat::Json => {
+            let serialized = serde_json::to_string_pretty(&selected)
+                .map_err(|err| format!("Failed to serialize granger export: {}", err))?;
+            fs::write(path, serialized)
+                .map_err(|err| format!("Failed to write granger export: {}", err))?;
+        }
+        ExchangeFormat::Csv => {
+            let mut writer = csv::Writer::from_path(path)
+                .map_err(|err| format!("Failed to create granger export: {}", err))?;
+            for animal in &selected {
+                writer
+                    .serialize(to_row(animal))
+                    .map_err(|err| format!("Failed to write granger export: {}", err))?;
+            }
+            writer
+                .flush()
+                .map_err(|err| format!("Failed to write granger export: {}", err))?;
+        }
+    }
+
+    Ok(selected.len())
+}
+
+pub fn import(
+    entries: &mut BTreeMap<String, GrangerAnimal>,
+    path: &str,
+    format: ExchangeFormat,
+    weights: &TraitWeights,
+) -> Result<ImportReport, String> {
+    let incoming: Vec<GrangerAnimal> = match format {
+        ExchangeFormat::Json => {
+            let raw = fs::read_to_string(path)
+                .map_err(|err| format!("Failed to read granger import: {}", err))?;
+            serde_json::from_str(&raw)
+                .map_err(|err| format!("Failed to parse granger import: {}", err))?
+        }
+        ExchangeFormat::Csv => {
+            let mut reader = csv::Reader::from_path(path)
+                .map_err(|err| format!("Failed to read granger import: {}", err))?;
+            let mut animals = Vec::new();
+            for (index, row) in reader.deserialize::<GrangerCsvRow>().enumerate() {
+                // Row 1 is the header.
+                let row = row.map_err(|err| {
+                    format!("Failed to parse granger import row {}: {}", index + 2, err)
+                })?;
+                animals.push(from_row(row)?);
+            }
+            animals
+        }
+    };
+
+    Ok(merge_all(entries, incoming, weights))
+}
+
+/// Merges animals from another herd into `entries`, rescoring them with the
+/// local trait weights.
+pub fn merge_all(
+    entries: &mut BTreeMap<String, GrangerAnimal>,
+    incoming: Vec<GrangerAnimal>,
+    weights: &TraitWeights,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+    for mut animal in incoming {
+        animal.breeding_score = breeding_score(&animal.canonical_traits, weights);
+        merge_incoming(entries, animal, weights, &mut report);
+    }
+    report
+}
+
+fn merge_incoming(
+    entries: &mut BTreeMap<String, GrangerAnimal>,
+    mut incoming: GrangerAnimal,
+    weights: &TraitWeights,
+    report: &mut ImportReport,
+) {
+    // Legacy ids are just the name, so a matching id only counts when the
+    // records also agree on who the animal is.
+    let same_id = entries.get(&incoming.id);
+    let target_id = if same_id.is_some_and(|existing| is_compatible(existing, &incoming)) {
+        Some(incoming.id.clone())
+    } else {
+        let candidates = match resolve_identity(entries, &incoming) {
+            IdentityMatch::Existing(id) => Ok(Some(id)),
+            IdentityMatch::New if same_id.is_none() => Ok(None),
+            // Another animal under this id; importing would overwrite it.
+            IdentityMatch::New => Err(vec![incoming.id.clone()]),
+            IdentityMatch::Ambiguous(candidates) => Err(candidates),
+        };
+        match candidates {
+            Ok(target_id) => target_id,
+            Err(candidates) => {
+                report.conflicts.push(ImportConflict {
+                    id: incoming.id.clone(),
+                    name: incoming.name.clone(),
+                    field: "identity",
+                    existing: Some(candidates.join(", ")),
+                    incoming: None,
+                });
+                return;
+            }
+        }
+    };
+
+    let Some(target_id) = target_id else {
+        if incoming.id.trim().is_empty() {
+            incoming.id = new_animal_id(entries, &incoming.name);
+        }
+        // Imported ambiguity and splits refer to ids in someone else's database.
+        incoming.ambiguous_with.clear();
+        incoming.distinct_from.clear();
+        entries.insert(incoming.id.clone(), incoming);
+        report.added += 1;
+        return;
+    };
+
+    let Some(existing) = entries.get_mut(&target_id) else {
+        return;
+    };
+
+    let mut changed = false;
+    let mut conflicts: Vec<(&'static str, Option<String>, Option<String>)> = Vec::new();
+
+    let compared = [
+        ("species", &mut existing.species, incoming.species),
+        ("sex", &mut existing.sex, incoming.sex),
+        (
+            "custom_label",
+            &mut existing.custom_label,
+            incoming.custom_label,
+        ),
+        ("settlement", &mut existing.settlement, incoming.settlement),
+        ("caretaker", &mut existing.caretaker, incoming.caretaker),
+        ("colour", &mut existing.colour, incoming.colour),
+        ("mother", &mut existing.mother, incoming.mother),
+        ("father", &mut existing.father, incoming.father),
+    ];
+    for (field, own, other) in compared {
+        match (own.as_deref(), other) {
+            (None, Some(other)) => {
+                *own = Some(other);
+                changed = true;
+            }
+            (Some(value), Some(other)) if !value.eq_ignore_ascii_case(&other) => {
+                conflicts.push((field, Some(value.to_string()), Some(other)));
+            }
+            _ => {}
+        }
+    }
+
+    changed |= fill_missing(&mut existing.age, incoming.age);
+    changed |= fill_missing(&mut existing.condition, incoming.condition);
+    changed |= fill_missing(&mut existing.genesis, incoming.genesis);
+    changed |= fill_missing(&mut existing.brand_owner, incoming.brand_owner);
+
+    match (existing.trait_points, incoming.trait_points) {
+        (None, Some(points)) => {
+            existing.trait_points = Some(points);
+            changed = true;
+        }
+        (Some(own), Some(other)) if own != other => {
+            conflicts.push((
+                "trait_points",
+                Some(own.to_string()),
+                Some(other.to_string()),
+            ));
+        }
+        _ => {}
+    }
+
+    if existing.canonical_traits.is_empty() && !incoming.canonical_traits.is_empty() {
+        existing.traits = incoming.traits;
+        existing.canonical_traits = incoming.canonical_traits;
+        existing.breeding_score = breeding_score(&existing.canonical_traits, weights);
+        changed = true;
+    } else if !incoming.canonical_traits.is_empty()
+        && !same_traits(&existing.canonical_traits, &incoming.canonical_traits)
+    {
+        conflicts.push((
+            "traits",
+            Some(existing.traits.join(LIST_SEPARATOR)),
+            Some(incoming.traits.join(LIST_SEPARATOR)),
+        ));
+    }
+
+    for tag in incoming.tags {
+        if !existing
+            .tags
+            .iter()
+            .any(|own| own.eq_ignore_ascii_case(&tag))
+        {
+            existing.tags.push(tag);
+            changed = true;
+        }
+    }
+    changed |= fill_missing(&mut existing.notes, incoming.notes);
+
+    if changed {
+        report.updated += 1;
+    } else {
+        report.unchanged += 1;
+    }
+
+    let name = existing.name.clone();
+    report
+        .conflicts
+        .extend(
+            conflicts
+                .into_iter()
+                .map(|(field, existing, incoming)| ImportConflict {
+                    id: target_id.clone(),
+                    name: name.clone(),
+                    field,
+                    existing,
+                    incoming,
+                }),
+        );
+}
+
+fn fill_missing(own: &mut Option<String>, other: Option<String>) -> bool {
+    if own.is_none() && other.is_some() {
+        *own = other;
+        true
+    } else {
+        false
+    }
+}
+
+fn same_traits(left: &[CreatureTrait], right: &[CreatureTrait]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .all(|creature_trait| right.contains(creature_trait))
+}
+
+fn to_row(animal: &GrangerAnimal) -> GrangerCsvRow {
+    GrangerCsvRow {
+        id: animal.id.clone(),
+        name: animal.name.clone(),
+        descriptors: animal.descriptors.join(LIST_SEPARATOR),
+        age: animal.age.clone(),
+        custom_label: animal.custom_label.clone(),
+        species: animal.species.clone(),
+        sex: animal.sex.clone(),
+        settlement: animal.settlement.clone(),
+        caretaker: animal.caretaker.clone(),
+        condition: animal.condition.clone(),
+        traits: animal.traits.join(LIST_SEPARATOR),
+        canonical_traits: animal
+            .canonical_traits
+            .iter()
+            .map(|creature_trait| trait_key(*creature_trait))
+            .collect::<Vec<_>>()
+            .join(LIST_SEPARATOR),
+        unrecognised_traits: animal.unrecognised_traits.join(LIST_SEPARATOR),
+        breeding_score: animal.breeding_score,
+        trait_points: animal.trait_points,
+        colour: animal.colour.clone(),
+        pregnant: animal.pregnant,
+        birth_due: animal.birth_due.clone(),
+        expected_birth_at: animal.expected_birth_at,
+        branded: animal.branded,
+        brand_owner: animal.brand_owner.clone(),
+        mother: animal.mother.clone(),
+        father: animal.father.clone(),
+        genesis: animal.genesis.clone(),
+        archived: animal.archived,
+        tags: animal.tags.join(LIST_SEPARATOR),
+        notes: animal.notes.clone(),
+        updated_at: animal.updated_at.clone(),
+    }
+}
+
+fn from_row(row: GrangerCsvRow) -> Result<GrangerAnimal, String> {
+    if row.name.trim().is_empty() {
+        return Err(format!("Granger import row {} has no name", row.id));
+    }
+
+    let canonical_traits = split_list(&row.canonical_traits)
+        .iter()
+        .map(|key| {
+            serde_json::from_value::<CreatureTrait>(serde_json::Value::String(key.clone()))
+                .map_err(|_| format!("Unknown trait in granger import: {}", key))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // The label column is informational; canonical keys are authoritative.
+    let traits = if canonical_traits.is_empty() {
+        split_list(&row.traits)
+    } else {
+        canonical_traits
+            .iter()
+            .map(|creature_trait| trait_label(*creature_trait).to_string())
+            .collect()
+    };
+
+    Ok(GrangerAnimal {
+        id: row.id,
+        name: row.name,
+        descriptors: split_list(&row.descriptors),
+        age: row.age,
+        custom_label: row.custom_label,
+        species: row.species,
+        sex: row.sex,
+        settlement: row.settlement,
+        caretaker: row.caretaker,
+        condition: row.condition,
+        traits,
+        canonical_traits,
+        unrecognised_traits: split_list(&row.unrecognised_traits),
+        breeding_score: row.breeding_score,
+        trait_points: row.trait_points,
+        colour: row.colour,
+        pregnant: row.pregnant,
+        birth_due: row.birth_due,
+        due_in_days: None,
+        expected_birth_at: row.expected_birth_at,
+        birth_notified: false,
+        branded: row.branded,
+        brand_owner: row.brand_owner,
+        mother: row.mother,
+        father: row.father,
+        genesis: row.genesis,
+        status_lines: Vec::new(),
+        raw_lines: Vec::new(),
+        updated_at: row.updated_at,
+        ambiguous_with: Vec::new(),
+        distinct_from: Vec::new(),
+        examinations: Vec::new(),
+        archived: row.archived,
+        tags: split_list(&row.tags),
+        notes: row.notes,
+        overridden_fields: Vec::new(),
+    })
+}
+
+fn trait_key(creature_trait: CreatureTrait) -> String {
+    serde_json::to_value(creature_trait)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn split_list(cell: &str) -> Vec<String> {
+    cell.split(';')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,120 @@
+use crate::granger::GrangerAnimal;
+use crate::granger_traits::CreatureTrait;
+use serde::{Deserialize, Serialize};
+
+/// What one examine showed, kept so changes can be followed over time.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExamineSnapshot {
+    pub examined_at: i64, // Unix timestamp
+    pub timestamp: String,
+    pub age: Option<String>,
+    pub condition: Option<String>,
+    pub settlement: Option<String>,
+    pub caretaker: Option<String>,
+    pub colour: Option<String>,
+    pub traits: Vec<String>,
+    pub canonical_traits: Vec<CreatureTrait>,
+    pub trait_points: Option<u32>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ExamineDiff {
+    pub animal_id: String,
+    pub previous_examined_at: i64,
+    pub latest_examined_at: i64,
+    pub changes: Vec<FieldChange>,
+    pub gained_traits: Vec<String>,
+    pub lost_traits: Vec<String>,
+}
+
+pub fn snapshot_of(animal: &GrangerAnimal, examined_at: i64) -> ExamineSnapshot {
+    ExamineSnapshot {
+        examined_at,
+        timestamp: animal.updated_at.clone(),
+        age: animal.age.clone(),
+        condition: animal.condition.clone(),
+        settlement: animal.settlement.clone(),
+        caretaker: animal.caretaker.clone(),
+        colour: animal.colour.clone(),
+        traits: animal.traits.clone(),
+        canonical_traits: animal.canonical_traits.clone(),
+        trait_points: animal.trait_points,
+    }
+}
+
+/// Adds an examine in time order. The full history is kept.
+pub fn push_examination(history: &mut Vec<ExamineSnapshot>, snapshot: ExamineSnapshot) {
+    history.push(snapshot);
+    history.sort_by_key(|entry| entry.examined_at);
+}
+
+/// Compares the two most recent examines of an animal, if it has two.
+pub fn diff_latest(animal: &GrangerAnimal) -> Option<ExamineDiff> {
+    let [.., previous, latest] = animal.examinations.as_slice() else {
+        return None;
+    };
+
+    let fields: [(&'static str, Option<String>, Option<String>); 6] = [
+        ("age", previous.age.clone(), latest.age.clone()),
+        (
+            "condition",
+            previous.condition.clone(),
+            latest.condition.clone(),
+        ),
+        (
+            "settlement",
+            previous.settlement.clone(),
+            latest.settlement.clone(),
+        ),
+        (
+            "caretaker",
+            previous.caretaker.clone(),
+            latest.caretaker.clone(),
+        ),
+        ("colour", previous.colour.clone(), latest.colour.clone()),
+        (
+            "trait_points",
+            previous.trait_points.map(|points| points.to_string()),
+            latest.trait_points.map(|points| points.to_string()),
+        ),
+    ];
+
+    let changes = fields
+        .into_iter()
+        .filter(|(_, before, after)| before != after)
+        .map(|(field, before, after)| FieldChange {
+            field,
+            before,
+            after,
+        })
+        .collect();
+
+    let gained_traits = latest
+        .traits
+        .iter()
+        .filter(|text| !previous.traits.contains(text))
+        .cloned()
+        .collect();
+    let lost_traits = previous
+        .traits
+        .iter()
+        .filter(|text| !latest.traits.contains(text))
+        .cloned()
+        .collect();
+
+    Some(ExamineDiff {
+        animal_id: animal.id.clone(),
+        previous_examined_at: previous.examined_at,
+        latest_examined_at: latest.examined_at,
+        changes,
+        gained_traits,
+        lost_traits,
+    })
+}
@@ -0,0 +1,347 @@
+use crate::granger::GrangerAnimal;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+
+// How many generations queries walk by default.
+pub const DEFAULT_GENERATIONS: u32 = 6;
+
+// Pairs sharing an ancestor within this many generations get an inbreeding warning.
+pub const INBREEDING_GENERATIONS: u32 = 3;
+
+#[derive(Clone, Serialize)]
+pub struct PedigreeRelative {
+    /// Stored animal the name resolved to, if it is in the herd.
+    pub id: Option<String>,
+    pub name: String,
+    /// The stored animal's species, or else that of the animal naming it.
+    pub species: Option<String>,
+    pub generation: u32,
+    /// Route from the queried animal, e.g. ["mother", "father"] for a
+    /// maternal grandfather or ["foal", "foal"] for a grandchild.
+    pub path: Vec<&'static str>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct LineageNode {
+    pub id: Option<String>,
+    pub name: String,
+    pub species: Option<String>,
+    pub sex: Option<String>,
+    pub colour: Option<String>,
+    pub traits: Vec<String>,
+    pub mother: Option<Box<LineageNode>>,
+    pub father: Option<Box<LineageNode>>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct InbreedingWarning {
+    pub shared_ancestors: Vec<String>,
+    /// Set when one animal appears in the other's ancestry.
+    pub direct_line: bool,
+}
+
+/// Resolves a parent name to the one stored animal of the same species whose
+/// sex fits the parent role. Names shared by several animals stay unresolved.
+fn resolve_parent<'a>(
+    entries: &'a BTreeMap<String, GrangerAnimal>,
+    child: &GrangerAnimal,
+    name: &str,
+    sex: &str,
+) -> Option<&'a GrangerAnimal> {
+    resolve_parent_among(entries.values(), child, name, sex)
+}
+
+fn resolve_parent_among<'a>(
+    animals: impl Iterator<Item = &'a GrangerAnimal>,
+    child: &GrangerAnimal,
+    name: &str,
+    sex: &str,
+) -> Option<&'a GrangerAnimal> {
+    let mut candidates = animals.filter(|animal| {
+        animal.id != child.id
+            && animal.name.eq_ignore_ascii_case(name)
+            && match (&animal.species, &child.species) {
+                (Some(left), Some(right)) => left.eq_ignore_ascii_case(right),
+                _ => true,
+            }
+            && animal.sex.as_deref().is_none_or(|own| own == sex)
+    });
+
+    let first = candidates.next()?;
+    // Two stored animals fit the name, so the parent cannot be pinned down.
+    if candidates.next().is_some() {
+        return None;
+    }
+    Some(first)
+}
+
+fn parents_of<'a>(
+    entries: &'a BTreeMap<String, GrangerAnimal>,
+    animal: &GrangerAnimal,
+) -> Vec<(&'static str, String, Option<&'a GrangerAnimal>)> {
+    let mut parents = Vec::new();
+    if let Some(mother) = &animal.mother {
+        parents.push((
+            "mother",
+            mother.clone(),
+            resolve_parent(entries, animal, mother, "female"),
+        ));
+    }
+    if let Some(father) = &animal.father {
+        parents.push((
+            "father",
+            father.clone(),
+            resolve_parent(entries, animal, father, "male"),
+        ));
+    }
+    parents
+}
+
+pub fn ancestors(
+    entries: &BTreeMap<String, GrangerAnimal>,
+    id: &str,
+    generations: u32,
+) -> Result<Vec<PedigreeRelative>, String> {
+    let animal = entries
+        .get(id)
+        .ok_or_else(|| format!("Animal not found: {}", id))?;
+
+    let mut relatives = Vec::new();
+    let mut visited = HashSet::from([animal.id.clone()]);
+    collect_ancestors(
+        entries,
+        animal,
+        1,
+        generations,
+        &mut Vec::new(),
+        &mut visited,
+        &mut relatives,
+    );
+    Ok(relatives)
+}
+
+fn collect_ancestors(
+    entries: &BTreeMap<String, GrangerAnimal>,
+    animal: &GrangerAnimal,
+    generation: u32,
+    generations: u32,
+    path: &mut Vec<&'static str>,
+    visited: &mut HashSet<String>,
+    relatives: &mut Vec<PedigreeRelative>,
+) {
+    if generation > generations {
+        return;
+    }
+
+    for (role, name, parent) in parents_of(entries, animal) {
+        path.push(role);
+        relatives.push(PedigreeRelative {
+            id: parent.map(|parent| parent.id.clone()),
+            name,
+            species: parent
+                .map_or(&animal.species, |parent| &parent.species)
+                .clone(),
+            generation,
+            path: path.clone(),
+        });
+        if let Some(parent) = parent {
+            // Guards against loops caused by misattributed parent names.
+            if visited.insert(parent.id.clone()) {
+                collect_ancestors(
+                    entries,
+                    parent,
+                    generation + 1,
+                    generations,
+                    path,
+                    visited,
+                    relatives,
+                );
+            }
+        }
+        path.pop();
+    }
+}
+
+pub fn descendants(
+    entries: &BTreeMap<String, GrangerAnimal>,
+    id: &str,
+    generations: u32,
+) -> Result<Vec<PedigreeRelative>, String> {
+    let animal = entries
+        .get(id)
+        .ok_or_else(|| format!("Animal not found: {}", id))?;
+
+    // Parent links are resolved once up front, looking names up in an index
+    // rather than scanning the herd, then walked generation by generation.
+    let mut by_name: HashMap<String, Vec<&GrangerAnimal>> = HashMap::new();
+    for animal in entries.values() {
+        by_name
+            .entry(animal.name.to_ascii_lowercase())
+            .or_default()
+            .push(animal);
+    }
+    let mut children: HashMap<&str, Vec<&GrangerAnimal>> = HashMap::new();
+    for child in entries.values() {
+        let parents = [(&child.mother, "female"), (&child.father, "male")];
+        for (name, sex) in parents {
+            let Some(name) = name else {
+                continue;
+            };
+            let named = by_name
+                .get(&name.to_ascii_lowercase())
+                .into_iter()
+                .flatten();
+            if let Some(parent) = resolve_parent_among(named.copied(), child, name, sex) {
+                children.entry(parent.id.as_str()).or_default().push(child);
+            }
+        }
+    }
+
+    let mut relatives = Vec::new();
+    let mut visited = HashSet::from([animal.id.clone()]);
+    let mut frontier = vec![(animal, Vec::new())];
+
+    for generation in 1..=generations {
+        let mut next = Vec::new();
+        for (parent, path) in frontier {
+            for child in children.get(parent.id.as_str()).into_iter().flatten() {
+                let child = *child;
+                if !visited.insert(child.id.clone()) {
+                    continue;
+                }
+
+                let mut child_path: Vec<&'static str> = path.clone();
+                child_path.push("foal");
+                relatives.push(PedigreeRelative {
+                    id: Some(child.id.clone()),
+                    name: child.name.clone(),
+                    species: child.species.clone(),
+                    generation,
+                    path: child_path.clone(),
+                });
+                next.push((child, child_path));
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    Ok(relatives)
+}
+
+/// Checks a planned pair for common ancestry within `INBREEDING_GENERATIONS`.
+pub fn inbreeding_warning(
+    entries: &BTreeMap<String, GrangerAnimal>,
+    male_id: &str,
+    female_id: &str,
+) -> Option<InbreedingWarning> {
+    let male_line = ancestors(entries, male_id, INBREEDING_GENERATIONS).ok()?;
+    let female_line = ancestors(entries, female_id, INBREEDING_GENERATIONS).ok()?;
+
+    // Ancestors not in the herd are only known by name, so one is shared
+    // when both lines name it for the same species.
+    let ancestor_keys = |line: &[PedigreeRelative]| -> BTreeMap<String, String> {
+        line.iter()
+            .map(|relative| {
+                let key = relative.id.clone().unwrap_or_else(|| {
+                    format!(
+                        "{}|{}",
+                        relative.name.to_ascii_lowercase(),
+                        relative
+                            .species
+                            .as_deref()
+                            .unwrap_or_default()
+                            .to_ascii_lowercase()
+                    )
+                });
+                (key, relative.name.clone())
+            })
+            .collect()
+    };
+    let male_keys = ancestor_keys(&male_line);
+    let female_keys = ancestor_keys(&female_line);
+
+    let direct_line = male_keys.contains_key(female_id) || female_keys.contains_key(male_id);
+    let shared_ancestors: Vec<String> = male_keys
+        .iter()
+        .filter(|(key, _)| female_keys.contains_key(*key))
+        .map(|(_, name)| name.clone())
+        .collect();
+
+    if shared_ancestors.is_empty() && !direct_line {
+        None
+    } else {
+        Some(InbreedingWarning {
+            shared_ancestors,
+            direct_line,
+        })
+    }
+}
+
+pub fn lineage(
+    entries: &BTreeMap<String, GrangerAnimal>,
+    id: &str,
+    generations: u32,
+) -> Result<LineageNode, String> {
+    let animal = entries
+        .get(id)
+        .ok_or_else(|| format!("Animal not found: {}", id))?;
+    let mut visited = HashSet::from([animal.id.clone()]);
+    Ok(lineage_node(entries, animal, generations, &mut visited))
+}
+
+fn lineage_node(
+    entries: &BTreeMap<String, GrangerAnimal>,
+    animal: &GrangerAnimal,
+    generations: u32,
+    visited: &mut HashSet<String>,
+) -> LineageNode {
+    let mut node = LineageNode {
+        id: Some(animal.id.clone()),
+        name: animal.name.clone(),
+        species: animal.species.clone(),
+        sex: animal.sex.clone(),
+        colour: animal.colour.clone(),
+        traits: animal.traits.clone(),
+        mother: None,
+        father: None,
+    };
+
+    if generations == 0 {
+        return node;
+    }
+
+    for (role, name, parent) in parents_of(entries, animal) {
+        let parent_node = match parent {
+            Some(parent) if visited.insert(parent.id.clone()) => {
+                lineage_node(entries, parent, generations - 1, visited)
+            }
+            _ => LineageNode {
+                id: parent.map(|parent| parent.id.clone()),
+                name,
+                species: animal.species.clone(),
+                sex: Some(if role == "mother" { "female" } else { "male" }.to_string()),
+                colour: None,
+                traits: Vec::new(),
+                mother: None,
+                father: None,
+            },
+        };
+        if role == "mother" {
+            node.mother = Some(Box::new(parent_node));
+        } else {
+            node.father = Some(Box::new(parent_node));
+        }
+    }
+
+    node
+}
+
+pub fn export_lineage(lineage: &LineageNode, path: &str) -> Result<(), String> {
+    let serialized = serde_json::to_string_pretty(lineage)
+        .map_err(|err| format!("Failed to serialize lineage: {}", err))?;
+    fs::write(path, serialized).map_err(|err| format!("Failed to write lineage: {}", err))
+}
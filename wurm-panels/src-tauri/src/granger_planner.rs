@@ -0,0 +1,160 @@
+use crate::granger::GrangerAnimal;
+use crate::granger_pedigree::{inbreeding_warning, InbreedingWarning};
+use crate::granger_traits::{weight, CreatureTrait, TraitWeights};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+// Rough odds of a foal inheriting a trait carried by one or both parents.
+const INHERIT_FROM_ONE_PARENT: f64 = 0.5;
+const INHERIT_FROM_BOTH_PARENTS: f64 = 0.85;
+
+// Score added per average trait point of the pair.
+const TRAIT_POINT_WEIGHT: f64 = 0.1;
+
+// Score added when both parents carry the colour the breeder is after.
+const COLOUR_MATCH_BONUS: f64 = 0.5;
+
+pub const DEFAULT_PAIRING_LIMIT: usize = 20;
+
+#[derive(Clone, Deserialize)]
+pub struct PairingRequest {
+    pub species: Option<String>,
+    pub preferred_colour: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct PairingSuggestion {
+    pub male_id: String,
+    pub male_name: String,
+    pub female_id: String,
+    pub female_name: String,
+    pub species: String,
+    pub expected_score: f64,
+    pub shared_positive_traits: Vec<CreatureTrait>,
+    pub negative_traits: Vec<CreatureTrait>,
+    pub colour_match: bool,
+    pub average_trait_points: Option<f64>,
+    /// Present when the pair shares recent ancestry.
+    pub inbreeding: Option<InbreedingWarning>,
+}
+
+/// Ranks every male/female pairing of the same species by expected offspring
+/// value, best first.
+pub fn plan_pairings(
+    entries: &BTreeMap<String, GrangerAnimal>,
+    weights: &TraitWeights,
+    request: &PairingRequest,
+) -> Vec<PairingSuggestion> {
+    let eligible: Vec<&GrangerAnimal> = entries
+        .values()
+        .filter(|animal| animal.ambiguous_with.is_empty() && !animal.archived)
+        .filter(|animal| animal.species.is_some())
+        .filter(|animal| match &request.species {
+            Some(species) => animal
+                .species
+                .as_deref()
+                .is_some_and(|own| own.eq_ignore_ascii_case(species)),
+            None => true,
+        })
+        .collect();
+
+    let males = eligible
+        .iter()
+        .filter(|animal| animal.sex.as_deref() == Some("male"));
+    let mut suggestions: Vec<PairingSuggestion> = males
+        .flat_map(|male| {
+            eligible
+                .iter()
+                .filter(|female| female.sex.as_deref() == Some("female"))
+                .filter(|female| same_species(male, female))
+                .map(|female| score_pair(male, female, weights, request))
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| b.expected_score.total_cmp(&a.expected_score));
+    suggestions.truncate(request.limit.unwrap_or(DEFAULT_PAIRING_LIMIT));
+    // Pedigree walks are costly, so only the pairs shown are checked.
+    for suggestion in &mut suggestions {
+        suggestion.inbreeding =
+            inbreeding_warning(entries, &suggestion.male_id, &suggestion.female_id);
+    }
+    suggestions
+}
+
+fn same_species(male: &GrangerAnimal, female: &GrangerAnimal) -> bool {
+    match (&male.species, &female.species) {
+        (Some(left), Some(right)) => left.eq_ignore_ascii_case(right),
+        _ => false,
+    }
+}
+
+fn score_pair(
+    male: &GrangerAnimal,
+    female: &GrangerAnimal,
+    weights: &TraitWeights,
+    request: &PairingRequest,
+) -> PairingSuggestion {
+    let male_traits: BTreeSet<CreatureTrait> = male.canonical_traits.iter().copied().collect();
+    let female_traits: BTreeSet<CreatureTrait> = female.canonical_traits.iter().copied().collect();
+
+    let mut expected_score = 0.0;
+    let mut shared_positive_traits = Vec::new();
+    let mut negative_traits = Vec::new();
+
+    for creature_trait in male_traits.union(&female_traits) {
+        let both = male_traits.contains(creature_trait) && female_traits.contains(creature_trait);
+        let chance = if both {
+            INHERIT_FROM_BOTH_PARENTS
+        } else {
+            INHERIT_FROM_ONE_PARENT
+        };
+        let trait_weight = weight(*creature_trait, weights);
+        expected_score += chance * trait_weight;
+
+        if trait_weight > 0.0 && both {
+            shared_positive_traits.push(*creature_trait);
+        } else if trait_weight < 0.0 {
+            negative_traits.push(*creature_trait);
+        }
+    }
+
+    let average_trait_points = match (male.trait_points, female.trait_points) {
+        (Some(left), Some(right)) => Some((left + right) as f64 / 2.0),
+        (Some(points), None) | (None, Some(points)) => Some(points as f64),
+        (None, None) => None,
+    };
+    if let Some(points) = average_trait_points {
+        expected_score += points * TRAIT_POINT_WEIGHT;
+    }
+
+    let colour_match = match &request.preferred_colour {
+        Some(colour) => [male, female].iter().all(|parent| {
+            parent
+                .colour
+                .as_deref()
+                .is_some_and(|own| own.eq_ignore_ascii_case(colour))
+        }),
+        None => match (&male.colour, &female.colour) {
+            (Some(left), Some(right)) => left.eq_ignore_ascii_case(right),
+            _ => false,
+        },
+    };
+    if colour_match && request.preferred_colour.is_some() {
+        expected_score += COLOUR_MATCH_BONUS;
+    }
+
+    PairingSuggestion {
+        male_id: male.id.clone(),
+        male_name: male.name.clone(),
+        female_id: female.id.clone(),
+        female_name: female.name.clone(),
+        species: male.species.clone().unwrap_or_default(),
+        expected_score,
+        shared_positive_traits,
+        negative_traits,
+        colour_match,
+        average_trait_points,
+        inbreeding: None,
+    }
+}
@@ -0,0 +1,129 @@
+use crate::granger::GrangerAnimal;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// Animals not examined for this many days count as lacking a recent examine.
+pub const DEFAULT_STALE_AFTER_DAYS: f64 = 7.0;
+
+// Group key for animals whose examine did not show the grouped field.
+const UNKNOWN_GROUP: &str = "Unknown";
+
+#[derive(Clone, Serialize)]
+pub struct GroupStats {
+    pub key: String,
+    pub count: usize,
+    pub males: usize,
+    pub females: usize,
+    pub unknown_sex: usize,
+    /// Animals per age word, e.g. "young" or "mature".
+    pub ages: BTreeMap<String, usize>,
+    pub average_breeding_score: f64,
+    pub average_trait_points: Option<f64>,
+    pub stale_examines: usize,
+}
+
+#[derive(Clone, Serialize)]
+pub struct HerdStats {
+    pub total: usize,
+    pub by_settlement: Vec<GroupStats>,
+    pub by_caretaker: Vec<GroupStats>,
+    pub by_species: Vec<GroupStats>,
+}
+
+/// Summarises the active herd. Archived animals are left out.
+pub fn herd_stats(
+    entries: &BTreeMap<String, GrangerAnimal>,
+    now: i64,
+    stale_after_days: f64,
+) -> HerdStats {
+    let active: Vec<&GrangerAnimal> = entries.values().filter(|animal| !animal.archived).collect();
+    let stale_before = now - (stale_after_days * 86_400.0).round() as i64;
+
+    HerdStats {
+        total: active.len(),
+        by_settlement: group_stats(&active, stale_before, |animal| &animal.settlement),
+        by_caretaker: group_stats(&active, stale_before, |animal| &animal.caretaker),
+        by_species: group_stats(&active, stale_before, |animal| &animal.species),
+    }
+}
+
+fn group_stats(
+    animals: &[&GrangerAnimal],
+    stale_before: i64,
+    key_of: impl Fn(&GrangerAnimal) -> &Option<String>,
+) -> Vec<GroupStats> {
+    let mut groups: BTreeMap<String, Vec<&GrangerAnimal>> = BTreeMap::new();
+    for animal in animals {
+        let key = key_of(animal)
+            .clone()
+            .unwrap_or_else(|| UNKNOWN_GROUP.to_string());
+        groups.entry(key).or_default().push(animal);
+    }
+
+    let mut stats: Vec<GroupStats> = groups
+        .into_iter()
+        .map(|(key, members)| summarise(key, &members, stale_before))
+        .collect();
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+    stats
+}
+
+fn summarise(key: String, members: &[&GrangerAnimal], stale_before: i64) -> GroupStats {
+    let count = members.len();
+    let males = members
+        .iter()
+        .filter(|animal| animal.sex.as_deref() == Some("male"))
+        .count();
+    let females = members
+        .iter()
+        .filter(|animal| animal.sex.as_deref() == Some("female"))
+        .count();
+
+    let mut ages: BTreeMap<String, usize> = BTreeMap::new();
+    for animal in members {
+        let age = animal
+            .age
+            .as_deref()
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_else(|| UNKNOWN_GROUP.to_ascii_lowercase());
+        *ages.entry(age).or_default() += 1;
+    }
+
+    let average_breeding_score = members
+        .iter()
+        .map(|animal| animal.breeding_score)
+        .sum::<f64>()
+        / count.max(1) as f64;
+
+    let points: Vec<u32> = members
+        .iter()
+        .filter_map(|animal| animal.trait_points)
+        .collect();
+    let average_trait_points = if points.is_empty() {
+        None
+    } else {
+        Some(points.iter().sum::<u32>() as f64 / points.len() as f64)
+    };
+
+    let stale_examines = members
+        .iter()
+        .filter(|animal| {
+            animal
+                .examinations
+                .last()
+                .is_none_or(|latest| latest.examined_at < stale_before)
+        })
+        .count();
+
+    GroupStats {
+        key,
+        count,
+        males,
+        females,
+        unknown_sex: count - males - females,
+        ages,
+        average_breeding_score,
+        average_trait_points,
+        stale_examines,
+    }
+}
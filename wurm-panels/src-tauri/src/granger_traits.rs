@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CreatureTrait {
+    FightsFiercely,
+    FleeterMovement,
+    ToughBugger,
+    StrongBody,
+    LightningMovement,
+    CarriesMore,
+    StrongLegMuscles,
+    KeenSenses,
+    StrongAndHealthy,
+    SparkInEyes,
+    MalformedHindlegs,
+    UnevenLegs,
+    OverlyAggressive,
+    Unmotivated,
+    StrongWilled,
+    SomeIllness,
+    ConstantlyHungry,
+    FeebleAndUnhealthy,
+    BredInCaptivity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraitKind {
+    Positive,
+    Negative,
+    Neutral,
+}
+
+/// User overrides for trait weights; traits without an entry use their default.
+pub type TraitWeights = BTreeMap<CreatureTrait, f64>;
+
+struct TraitDefinition {
+    creature_trait: CreatureTrait,
+    label: &'static str,
+    kind: TraitKind,
+    default_weight: f64,
+    // Lowercase fragments that identify the trait in an examine sentence.
+    phrases: &'static [&'static str],
+}
+
+const CATALOGUE: &[TraitDefinition] = &[
+    TraitDefinition {
+        creature_trait: CreatureTrait::FightsFiercely,
+        label: "Fights fiercely",
+        kind: TraitKind::Positive,
+        default_weight: 1.0,
+        phrases: &["fight fiercely", "fights fiercely"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::FleeterMovement,
+        label: "Fleeter movement",
+        kind: TraitKind::Positive,
+        default_weight: 1.0,
+        phrases: &["fleeter movement"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::ToughBugger,
+        label: "Tough bugger",
+        kind: TraitKind::Positive,
+        default_weight: 1.0,
+        phrases: &["tough bugger"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::StrongBody,
+        label: "Strong body",
+        kind: TraitKind::Positive,
+        default_weight: 1.0,
+        phrases: &["strong body"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::LightningMovement,
+        label: "Lightning movement",
+        kind: TraitKind::Positive,
+        default_weight: 1.0,
+        phrases: &["lightning movement"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::CarriesMore,
+        label: "Carries more than average",
+        kind: TraitKind::Positive,
+        default_weight: 1.0,
+        phrases: &["carry more than average"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::StrongLegMuscles,
+        label: "Strong leg muscles",
+        kind: TraitKind::Positive,
+        default_weight: 1.0,
+        phrases: &["strong leg muscles"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::KeenSenses,
+        label: "Keen senses",
+        kind: TraitKind::Positive,
+        default_weight: 1.0,
+        phrases: &["keen senses"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::StrongAndHealthy,
+        label: "Strong and healthy",
+        kind: TraitKind::Positive,
+        default_weight: 1.0,
+        phrases: &["unusually strong and healthy"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::SparkInEyes,
+        label: "Spark in its eyes",
+        kind: TraitKind::Positive,
+        default_weight: 1.0,
+        phrases: &["certain spark in"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::MalformedHindlegs,
+        label: "Malformed hindlegs",
+        kind: TraitKind::Negative,
+        default_weight: -1.0,
+        phrases: &["malformed hindlegs"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::UnevenLegs,
+        label: "Legs of different length",
+        kind: TraitKind::Negative,
+        default_weight: -1.0,
+        phrases: &["of different length"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::OverlyAggressive,
+        label: "Overly aggressive",
+        kind: TraitKind::Negative,
+        default_weight: -1.0,
+        phrases: &["overly aggressive"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::Unmotivated,
+        label: "Very unmotivated",
+        kind: TraitKind::Negative,
+        default_weight: -1.0,
+        phrases: &["very unmotivated"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::StrongWilled,
+        label: "Strong willed",
+        kind: TraitKind::Negative,
+        default_weight: -1.0,
+        phrases: &["strong willed"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::SomeIllness,
+        label: "Some illness",
+        kind: TraitKind::Negative,
+        default_weight: -1.0,
+        phrases: &["some illness"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::ConstantlyHungry,
+        label: "Constantly hungry",
+        kind: TraitKind::Negative,
+        default_weight: -1.0,
+        phrases: &["constantly hungry"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::FeebleAndUnhealthy,
+        label: "Feeble and unhealthy",
+        kind: TraitKind::Negative,
+        default_weight: -1.0,
+        phrases: &["feeble and unhealthy"],
+    },
+    TraitDefinition {
+        creature_trait: CreatureTrait::BredInCaptivity,
+        label: "Bred in captivity",
+        kind: TraitKind::Neutral,
+        default_weight: 0.0,
+        phrases: &["bred in captivity"],
+    },
+];
+
+#[derive(Clone, Serialize)]
+pub struct TraitCatalogueEntry {
+    pub creature_trait: CreatureTrait,
+    pub label: &'static str,
+    pub kind: TraitKind,
+    pub default_weight: f64,
+    pub weight: f64,
+}
+
+fn definition(creature_trait: CreatureTrait) -> &'static TraitDefinition {
+    CATALOGUE
+        .iter()
+        .find(|definition| definition.creature_trait == creature_trait)
+        .expect("every creature trait has a catalogue entry")
+}
+
+/// Maps an examine sentence to the trait it describes, if it is a known one.
+pub fn classify_sentence(sentence: &str) -> Option<CreatureTrait> {
+    let lowered = sentence.to_lowercase();
+    CATALOGUE
+        .iter()
+        .find(|definition| {
+            definition
+                .phrases
+                .iter()
+                .any(|phrase| lowered.contains(phrase))
+        })
+        .map(|definition| definition.creature_trait)
+}
+
+pub fn label(creature_trait: CreatureTrait) -> &'static str {
+    definition(creature_trait).label
+}
+
+pub fn weight(creature_trait: CreatureTrait, weights: &TraitWeights) -> f64 {
+    weights
+        .get(&creature_trait)
+        .copied()
+        .unwrap_or_else(|| definition(creature_trait).default_weight)
+}
+
+pub fn breeding_score(traits: &[CreatureTrait], weights: &TraitWeights) -> f64 {
+    traits
+        .iter()
+        .map(|creature_trait| weight(*creature_trait, weights))
+        .sum()
+}
+
+pub fn catalogue(weights: &TraitWeights) -> Vec<TraitCatalogueEntry> {
+    CATALOGUE
+        .iter()
+        .map(|definition| TraitCatalogueEntry {
+            creature_trait: definition.creature_trait,
+            label: definition.label,
+            kind: definition.kind,
+            default_weight: definition.default_weight,
+            weight: weight(definition.creature_trait, weights),
+        })
+        .collect()
+}
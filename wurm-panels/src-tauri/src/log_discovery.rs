@@ -0,0 +1,305 @@
+use directories::BaseDirs;
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+// Folders under a home directory or Steam library that may hold a `players`
+// directory, covering the standalone client and the Steam builds.
+const INSTALL_SUFFIXES: &[&str] = &[
+    "wurm",
+    "Wurm",
+    "Wurm Online",
+    "steamapps/common/Wurm Online/gamedata",
+    "steamapps/common/Wurm Unlimited/WurmLauncher/PlayerFiles",
+];
+
+#[derive(Clone, Serialize)]
+pub struct CharacterLogDir {
+    pub character: String,
+    pub install_dir: String,
+    pub logs_dir: String,
+    pub log_count: usize,
+    pub last_activity: Option<i64>, // Unix timestamp of the newest log write
+}
+
+// Every character writes these, so their presence tells Wurm logs apart
+// from any other folder of text files.
+const CORE_LOG_PREFIXES: &[&str] = &["_Event", "_Skills"];
+
+// Logs this old suggest a character that is no longer played.
+const STALE_LOG_SECS: i64 = 30 * 24 * 60 * 60;
+
+#[derive(Clone, Serialize)]
+pub struct LogDirectoryDiscovery {
+    /// Most recently active first.
+    pub candidates: Vec<CharacterLogDir>,
+    pub suggested: Option<String>,
+}
+
+#[derive(Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticLevel {
+    Ok,
+    Warning,
+    /// The folder cannot be watched; the setting is not saved.
+    Error,
+}
+
+/// What was found at a prospective watch directory.
+#[derive(Clone, Serialize)]
+pub struct WatchDirDiagnostics {
+    pub path: String,
+    pub level: DiagnosticLevel,
+    pub message: String,
+    pub log_count: usize,
+    pub last_activity: Option<i64>, // Unix timestamp of the newest log write
+    /// Log folders under `path`, when it is an install, `players` or
+    /// character folder rather than a logs folder.
+    pub suggestions: Vec<String>,
+}
+
+fn search_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Some(dirs) = BaseDirs::new() {
+        roots.push(dirs.home_dir().to_path_buf());
+        roots.push(dirs.home_dir().join(".steam/steam"));
+        roots.push(dirs.data_dir().join("Steam"));
+    }
+    for variable in ["ProgramFiles(x86)", "ProgramFiles"] {
+        if let Ok(program_files) = env::var(variable) {
+            roots.push(PathBuf::from(program_files).join("Steam"));
+        }
+    }
+    roots.push(PathBuf::from("/Applications/Steam"));
+    roots
+}
+
+/// Returns every install directory that has a `players` folder.
+fn install_dirs() -> Vec<PathBuf> {
+    let mut installs: Vec<PathBuf> = Vec::new();
+    for root in search_roots() {
+        for suffix in INSTALL_SUFFIXES {
+            let candidate = root.join(suffix);
+            if !candidate.join("players").is_dir() {
+                continue;
+            }
+            // "wurm" and "Wurm" are the same folder on case-insensitive systems.
+            let canonical = fs::canonicalize(&candidate).unwrap_or(candidate);
+            if !installs.contains(&canonical) {
+                installs.push(canonical);
+            }
+        }
+    }
+    installs
+}
+
+/// Finds `players/*/logs` under the usual Wurm install locations.
+pub fn discover() -> LogDirectoryDiscovery {
+    let mut candidates: Vec<CharacterLogDir> = install_dirs()
+        .iter()
+        .flat_map(|install| characters_in(install))
+        .collect();
+
+    candidates.sort_by(|a, b| {
+        b.last_activity
+            .cmp(&a.last_activity)
+            .then_with(|| a.character.cmp(&b.character))
+    });
+    let suggested = candidates
+        .first()
+        .map(|candidate| candidate.logs_dir.clone());
+
+    LogDirectoryDiscovery {
+        candidates,
+        suggested,
+    }
+}
+
+fn characters_in(install: &Path) -> Vec<CharacterLogDir> {
+    let Ok(players) = fs::read_dir(install.join("players")) else {
+        return Vec::new();
+    };
+
+    players
+        .flatten()
+        .filter_map(|player| {
+            let logs_dir = player.path().join("logs");
+            if !logs_dir.is_dir() {
+                return None;
+            }
+            let (log_count, last_activity) = log_activity(&logs_dir);
+            Some(CharacterLogDir {
+                character: player.file_name().to_string_lossy().to_string(),
+                install_dir: install.to_string_lossy().to_string(),
+                logs_dir: logs_dir.to_string_lossy().to_string(),
+                log_count,
+                last_activity,
+            })
+        })
+        .collect()
+}
+
+/// Checks that a folder exists, can be read and holds Wurm logs. An empty
+/// path is fine; it switches watching off.
+pub fn diagnose(watch_dir: &str, now: i64) -> WatchDirDiagnostics {
+    let mut diagnostics = WatchDirDiagnostics {
+        path: watch_dir.to_string(),
+        level: DiagnosticLevel::Ok,
+        message: String::new(),
+        log_count: 0,
+        last_activity: None,
+        suggestions: Vec::new(),
+    };
+    let mut fail = |level, message: String| {
+        diagnostics.level = level;
+        diagnostics.message = message;
+    };
+
+    let path = Path::new(watch_dir);
+    if watch_dir.trim().is_empty() {
+        fail(
+            DiagnosticLevel::Ok,
+            "No folder set; logs will not be watched.".to_string(),
+        );
+    } else if !path.exists() {
+        fail(
+            DiagnosticLevel::Error,
+            "This folder does not exist.".to_string(),
+        );
+    } else if !path.is_dir() {
+        fail(
+            DiagnosticLevel::Error,
+            "This is a file, not a folder.".to_string(),
+        );
+    } else if let Err(err) = fs::read_dir(path) {
+        fail(
+            DiagnosticLevel::Error,
+            format!("This folder cannot be read: {}", err),
+        );
+    } else {
+        let (log_count, last_activity) = log_activity(path);
+        let core_logs = has_core_logs(path);
+        diagnostics.log_count = log_count;
+        diagnostics.last_activity = last_activity;
+        diagnostics.suggestions = nested_log_dirs(path);
+
+        if log_count == 0 || !core_logs {
+            let found = if log_count == 0 {
+                "No log files found.".to_string()
+            } else {
+                format!(
+                    "Found {} text files, but none look like Wurm logs.",
+                    log_count
+                )
+            };
+            let hint = match diagnostics.suggestions.as_slice() {
+                [] => " Pick a character's players/<name>/logs folder.".to_string(),
+                [only] => format!(" Did you mean {}?", only),
+                many => format!(
+                    " This looks like a Wurm folder; did you mean one of {} character log folders?",
+                    many.len()
+                ),
+            };
+            diagnostics.level = DiagnosticLevel::Error;
+            diagnostics.message = format!("{}{}", found, hint);
+        } else {
+            let newest = last_activity
+                .map(|modified| format!(", newest {}", describe_age(now - modified)))
+                .unwrap_or_default();
+            diagnostics.message = format!("Found {} logs{}.", log_count, newest);
+            if last_activity.is_some_and(|modified| now - modified > STALE_LOG_SECS) {
+                diagnostics.level = DiagnosticLevel::Warning;
+                diagnostics
+                    .message
+                    .push_str(" Nothing has been logged recently; is this the character you play?");
+            }
+        }
+    }
+    diagnostics
+}
+
+fn has_core_logs(logs_dir: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(logs_dir) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let name = entry.file_name().to_string_lossy().to_string();
+        CORE_LOG_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+    })
+}
+
+// Finds logs folders when given an install (`players/*/logs`), a `players`
+// folder (`*/logs`) or a character folder (`logs`).
+fn nested_log_dirs(dir: &Path) -> Vec<String> {
+    if dir.join("logs").is_dir() {
+        return vec![dir.join("logs").to_string_lossy().to_string()];
+    }
+    let players = if dir.join("players").is_dir() {
+        dir.join("players")
+    } else {
+        dir.to_path_buf()
+    };
+    let Ok(entries) = fs::read_dir(&players) else {
+        return Vec::new();
+    };
+    let mut found: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path().join("logs"))
+        .filter(|logs_dir| logs_dir.is_dir())
+        .map(|logs_dir| logs_dir.to_string_lossy().to_string())
+        .collect();
+    found.sort();
+    found
+}
+
+fn describe_age(seconds: i64) -> String {
+    let plural = |count: i64, unit: &str| {
+        format!(
+            "{} {}{} ago",
+            count,
+            unit,
+            if count == 1 { "" } else { "s" }
+        )
+    };
+    match seconds.max(0) {
+        0..=59 => "just now".to_string(),
+        seconds @ 60..=3599 => plural(seconds / 60, "minute"),
+        seconds @ 3600..=86_399 => plural(seconds / 3600, "hour"),
+        seconds => plural(seconds / 86_400, "day"),
+    }
+}
+
+/// Counts the `.txt` logs in a directory and finds when one was last written.
+pub fn log_activity(logs_dir: &Path) -> (usize, Option<i64>) {
+    let Ok(entries) = fs::read_dir(logs_dir) else {
+        return (0, None);
+    };
+
+    let mut count = 0;
+    let mut newest: Option<i64> = None;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_log = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("txt"));
+        if !is_log {
+            continue;
+        }
+        count += 1;
+
+        let modified = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|elapsed| elapsed.as_secs() as i64);
+        if modified > newest {
+            newest = modified;
+        }
+    }
+    (count, newest)
+}
@@ -0,0 +1,156 @@
+use directories::ProjectDirs;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing::{info, warn};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::reload;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Registry};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Used until settings are loaded, and when the saved filter is invalid.
+pub const DEFAULT_LOG_FILTER: &str = "info";
+
+/// Overrides the saved filter, e.g. `WURM_PANELS_LOG=debug` for a session.
+pub const LOG_FILTER_ENV: &str = "WURM_PANELS_LOG";
+
+const LOG_FILE_PREFIX: &str = "wurm-panels";
+const LOG_FILE_SUFFIX: &str = "log";
+
+// One file per day; older ones are deleted as new ones start.
+const KEPT_LOG_FILES: usize = 7;
+
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+pub fn log_dir_path() -> Result<PathBuf, String> {
+    ProjectDirs::from("com", "WefNET", "wurm-sales")
+        .map(|dirs| dirs.data_dir().join("logs"))
+        .ok_or_else(|| "Unable to resolve data directory".to_string())
+}
+
+/// Sends `tracing` events to daily log files in the app data dir, and to
+/// stdout for the dev console. Call once, before anything logs.
+pub fn init() {
+    let (filter, handle) = reload::Layer::new(initial_filter());
+
+    let file_layer = match log_dir_path().and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix(LOG_FILE_SUFFIX)
+            .max_log_files(KEPT_LOG_FILES)
+            .build(&dir)
+            .map_err(|err| format!("Failed to open log directory {:?}: {}", dir, err))
+    }) {
+        Ok(appender) => Some(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(appender),
+        ),
+        Err(err) => {
+            eprintln!("{}; logging to stdout only", err);
+            None
+        }
+    };
+
+    let initialised = tracing_subscriber::registry()
+        .with(filter)
+        .with(file_layer)
+        .with(tracing_subscriber::fmt::layer().compact())
+        .try_init();
+    if initialised.is_ok() {
+        let _ = FILTER_HANDLE.set(handle);
+    }
+}
+
+fn initial_filter() -> EnvFilter {
+    EnvFilter::try_from_env(LOG_FILTER_ENV).unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER))
+}
+
+/// Parses a filter such as `info,wurm_panels::watcher=debug`.
+pub fn parse_filter(filter: &str) -> Result<EnvFilter, String> {
+    EnvFilter::try_new(filter).map_err(|err| format!("Invalid log filter '{}': {}", filter, err))
+}
+
+/// Switches to the saved per-module levels. The environment variable still
+/// wins when it is set.
+pub fn apply_filter(filter: &str) -> Result<(), String> {
+    if std::env::var_os(LOG_FILTER_ENV).is_some() {
+        info!("{} is set; ignoring saved log filter", LOG_FILTER_ENV);
+        return Ok(());
+    }
+    let parsed = parse_filter(filter)?;
+    let Some(handle) = FILTER_HANDLE.get() else {
+        return Err("Logging is not initialised".to_string());
+    };
+    handle
+        .reload(parsed)
+        .map_err(|err| format!("Failed to apply log filter: {}", err))?;
+    info!("Log filter set to {}", filter);
+    Ok(())
+}
+
+// Newest last.
+fn log_files() -> Result<Vec<PathBuf>, String> {
+    let dir = log_dir_path()?;
+    let entries =
+        fs::read_dir(&dir).map_err(|err| format!("Failed to read log directory: {}", err))?;
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with(LOG_FILE_PREFIX))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Zips every kept log file into `path`, for attaching to a bug report.
+/// Returns how many files went in.
+pub fn collect(path: &str) -> Result<usize, String> {
+    let files = log_files()?;
+    let file = File::create(path).map_err(|err| format!("Failed to create {}: {}", path, err))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let header = format!(
+        "wurm-panels {}\n{} {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    zip.start_file("about.txt", options)
+        .map_err(|err| err.to_string())
+        .and_then(|_| {
+            zip.write_all(header.as_bytes())
+                .map_err(|err| err.to_string())
+        })
+        .map_err(|err| format!("Failed to write log bundle: {}", err))?;
+
+    for log_file in &files {
+        let Some(name) = log_file.file_name() else {
+            continue;
+        };
+        let contents = match fs::read(log_file) {
+            Ok(contents) => contents,
+            Err(err) => {
+                warn!("Failed to read {:?} for log bundle: {}", log_file, err);
+                continue;
+            }
+        };
+        zip.start_file(name.to_string_lossy(), options)
+            .map_err(|err| err.to_string())
+            .and_then(|_| zip.write_all(&contents).map_err(|err| err.to_string()))
+            .map_err(|err| format!("Failed to write log bundle: {}", err))?;
+    }
+    zip.finish()
+        .map_err(|err| format!("Failed to finish log bundle: {}", err))?;
+
+    Ok(files.len())
+}
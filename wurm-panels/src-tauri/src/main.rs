@@ -3,28 +3,64 @@
 
 mod app_settings;
 mod auth_client;
+mod backup;
 mod community_deeds_persistence;
+mod database;
 mod granger;
+mod granger_births;
+mod granger_exchange;
+mod granger_history;
+mod granger_pedigree;
+mod granger_planner;
+mod granger_stats;
+mod granger_traits;
+mod log_discovery;
+mod logging;
+mod merchant_sales;
+mod persistence;
+mod price_history;
+mod profiles;
 mod skill_sessions;
 mod trade_entries;
+mod trade_profiles;
 mod user_layers_persistence;
 mod watcher;
+mod window_state;
 
 use app_settings::{
     load_settings_from_disk, new_shared as new_settings_store, persist_settings, AppSettings,
-    SharedSettings,
+    SettingsNotice, SettingsUpdate, SharedSettings, SharedSettingsNotice, WindowState,
 };
 use auth_client::{AuthApiClient, VerifiedSession};
+use backup::{BackupManifest, RestoreMode, RestoreReport};
 use granger::{
     load_from_disk as load_granger_from_disk, new_store_with as new_granger_store_with,
-    GrangerAnimal, SharedGrangerEntries,
+    GrangerAnimal, GrangerAnimalEdit, SharedGrangerEntries,
+};
+use granger_births::UpcomingBirth;
+use granger_exchange::{ExchangeFormat, GrangerFilter, ImportReport};
+use granger_history::{ExamineDiff, ExamineSnapshot};
+use granger_pedigree::{LineageNode, PedigreeRelative};
+use granger_planner::{PairingRequest, PairingSuggestion};
+use granger_stats::HerdStats;
+use granger_traits::{TraitCatalogueEntry, TraitWeights};
+use log_discovery::{DiagnosticLevel, LogDirectoryDiscovery, WatchDirDiagnostics};
+use merchant_sales::{
+    load_from_disk as load_merchant_sales_from_disk,
+    new_store_with as new_merchant_sales_store_with, IncomeBucket, IncomePeriod, MerchantSale,
+    SharedMerchantSales,
+};
+use price_history::{
+    load_from_disk as load_price_history_from_disk, new_store_with as new_price_history_store_with,
+    PriceCheck, SharedPriceHistory,
 };
 use serde::Deserialize;
 use skill_sessions::{new_store as new_skill_session_store, SharedSkillSessions, SkillSessionData};
+use std::collections::BTreeMap;
 use std::env;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
-    Arc,
+    Arc, Mutex,
 };
 use tauri::{
     menu::{MenuBuilder, MenuItem},
@@ -33,15 +69,27 @@ use tauri::{
     async_runtime,
     Emitter, Manager,
 };
+use tauri_plugin_opener::OpenerExt;
 use image::GenericImageView;
+use tracing::{debug, error, info, warn};
 use trade_entries::{new_store as new_trade_store, SharedTradeEntries, TradeEntry};
+use trade_profiles::TradeProfile;
 use url::Url;
 use watcher::DirectoryWatcher;
 use tokio::time::sleep;
 
 #[derive(Deserialize)]
 struct UpdateSettingsPayload {
-    watch_dir: String,
+    /// Profile to switch to before applying the other fields. Unknown names
+    /// create a new profile.
+    profile: Option<String>,
+    watch_dir: Option<String>,
+    trade_watchlist: Option<Vec<String>>,
+    skill_targets: Option<BTreeMap<String, f64>>,
+    auto_switch_profiles: Option<bool>,
+    /// Save the watch dir even if validation finds no Wurm logs there.
+    #[serde(default)]
+    force: bool,
 }
 
 #[derive(Deserialize)]
@@ -49,32 +97,106 @@ struct VerifySessionPayload {
     token: String,
 }
 
+#[derive(Deserialize)]
+struct MergeGrangerAnimalsPayload {
+    source_id: String,
+    target_id: String,
+}
+
+#[derive(Deserialize)]
+struct SplitGrangerAnimalPayload {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct GrangerAnimalPayload {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct ArchiveGrangerAnimalPayload {
+    id: String,
+    archived: bool,
+}
+
+#[derive(Deserialize)]
+struct EditGrangerAnimalPayload {
+    id: String,
+    edit: GrangerAnimalEdit,
+}
+
+#[derive(Deserialize)]
+struct GrangerTagsPayload {
+    id: String,
+    tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct GrangerNotesPayload {
+    id: String,
+    notes: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ExportGrangerPayload {
+    path: String,
+    format: Option<ExchangeFormat>,
+    #[serde(default)]
+    filter: GrangerFilter,
+}
+
+#[derive(Deserialize)]
+struct ImportGrangerPayload {
+    path: String,
+    format: Option<ExchangeFormat>,
+}
+
+#[derive(Deserialize)]
+struct RestoreBackupPayload {
+    path: String,
+    mode: RestoreMode,
+}
+
+#[derive(Deserialize)]
+struct PedigreeQueryPayload {
+    id: String,
+    generations: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct ExportLineagePayload {
+    id: String,
+    generations: Option<u32>,
+    path: Option<String>,
+}
+
 #[derive(Deserialize)]
 struct SetAlwaysOnTopPayload {
     window_label: String,
     always_on_top: bool,
 }
 
+#[derive(Deserialize)]
+struct SetWindowOpacityPayload {
+    window_label: String,
+    opacity: f64,
+}
+
 #[tauri::command]
 async fn open_skills_window(
     app: tauri::AppHandle,
     skill_state: tauri::State<'_, SharedSkillSessions>,
-    settings_state: tauri::State<'_, SharedSettings>,
 ) -> Result<(), String> {
     if let Some(existing) = app.get_webview_window("skills") {
-        println!("Skills window already open; showing existing instance");
+        debug!("Skills window already open; showing existing instance");
         let _ = existing.show();
         return Ok(());
     }
 
     let url = tauri::WebviewUrl::App("skills.html".into());
 
-    let placement = {
-        let settings = settings_state
-            .lock()
-            .map_err(|e| format!("Failed to access settings: {}", e))?;
-        settings.skills_window.clone()
-    };
+    let saved = window_state::saved(&app, "skills");
+    let placement = saved.placement.clone().unwrap_or_default();
 
     let width = placement.width.max(420.0);
     let height = placement.height.max(140.0);
@@ -88,7 +210,7 @@ async fn open_skills_window(
         .resizable(true)
         .decorations(false)
         .minimizable(false)
-        .always_on_top(true)
+        .always_on_top(saved.always_on_top.unwrap_or(true))
         .skip_taskbar(true)
         .focusable(false)
         .transparent(true)
@@ -96,20 +218,21 @@ async fn open_skills_window(
         .build()
     {
         Ok(window) => {
-            println!("Skills window created successfully from Rust");
+            debug!("Skills window created successfully from Rust");
+            window_state::opened(&app, &window);
 
             if let Ok(sessions) = skill_state.lock() {
                 let session_vec: Vec<SkillSessionData> = sessions.values().cloned().collect();
 
                 if let Err(err) = window.emit("skill-sessions", session_vec) {
-                    println!("Failed to send initial data to skills window: {:?}", err);
+                    warn!("Failed to send initial data to skills window: {:?}", err);
                 }
             }
 
             Ok(())
         }
         Err(e) => {
-            println!("Failed to create skills window: {:?}", e);
+            warn!("Failed to create skills window: {:?}", e);
             Err(format!("Failed to create window: {:?}", e))
         }
     }
@@ -118,26 +241,27 @@ async fn open_skills_window(
 #[tauri::command]
 async fn open_farming_grind_window(app: tauri::AppHandle) -> Result<(), String> {
     if let Some(existing) = app.get_webview_window("farming-grind") {
-        println!("Farming Grind window already open; showing existing instance");
+        debug!("Farming Grind window already open; showing existing instance");
         let _ = existing.show();
         return Ok(());
     }
 
     let url = tauri::WebviewUrl::App("farming-grind-calc.html".into());
 
-    match tauri::webview::WebviewWindowBuilder::new(&app, "farming-grind", url)
+    let builder = tauri::webview::WebviewWindowBuilder::new(&app, "farming-grind", url)
         .title("Farming Grind Calc")
         .inner_size(600.0, 520.0)
         .resizable(false)
-        .decorations(false)
-        .build()
-    {
-        Ok(_window) => {
-            println!("Farming Grind window created successfully from Rust");
+        .decorations(false);
+
+    match window_state::apply(builder, &window_state::saved(&app, "farming-grind")).build() {
+        Ok(window) => {
+            debug!("Farming Grind window created successfully from Rust");
+            window_state::opened(&app, &window);
             Ok(())
         }
         Err(e) => {
-            println!("Failed to create Farming Grind window: {:?}", e);
+            warn!("Failed to create Farming Grind window: {:?}", e);
             Err(format!("Failed to create window: {:?}", e))
         }
     }
@@ -163,20 +287,21 @@ async fn open_settings_window(
 
     let url = tauri::WebviewUrl::App("settings.html".into());
 
-    match tauri::webview::WebviewWindowBuilder::new(&app, "settings", url)
+    let builder = tauri::webview::WebviewWindowBuilder::new(&app, "settings", url)
         .title("Settings")
         .inner_size(480.0, 260.0)
         .resizable(false)
         .decorations(false)
-        .skip_taskbar(true)
-        .build()
-    {
+        .skip_taskbar(true);
+
+    match window_state::apply(builder, &window_state::saved(&app, "settings")).build() {
         Ok(window) => {
+            window_state::opened(&app, &window);
             let _ = window.emit("settings-data", current_settings);
             Ok(())
         }
         Err(e) => {
-            println!("Failed to create settings window: {:?}", e);
+            warn!("Failed to create settings window: {:?}", e);
             Err(format!("Failed to create settings window: {:?}", e))
         }
     }
@@ -208,21 +333,22 @@ async fn open_trade_window(
     trade_state: tauri::State<'_, SharedTradeEntries>,
 ) -> Result<(), String> {
     if let Some(existing) = app.get_webview_window("trade") {
-        println!("Trade window already open; showing existing instance");
+        debug!("Trade window already open; showing existing instance");
         let _ = existing.show();
         return Ok(());
     }
 
     let url = tauri::WebviewUrl::App("trade.html".into());
 
-    match tauri::webview::WebviewWindowBuilder::new(&app, "trade", url)
+    let builder = tauri::webview::WebviewWindowBuilder::new(&app, "trade", url)
         .title("Wurm Trade Monitor")
         .inner_size(720.0, 420.0)
         .resizable(false)
-        .decorations(false)
-        .build()
-    {
+        .decorations(false);
+
+    match window_state::apply(builder, &window_state::saved(&app, "trade")).build() {
         Ok(window) => {
+            window_state::opened(&app, &window);
             if let Ok(trades) = trade_state.lock() {
                 let snapshot = trades.clone();
                 let _ = window.emit("trade-entries", snapshot);
@@ -230,7 +356,7 @@ async fn open_trade_window(
             Ok(())
         }
         Err(e) => {
-            println!("Failed to create trade window: {:?}", e);
+            warn!("Failed to create trade window: {:?}", e);
             Err(format!("Failed to create trade window: {:?}", e))
         }
     }
@@ -239,11 +365,86 @@ async fn open_trade_window(
 #[tauri::command]
 async fn get_trade_entries(
     trade_state: tauri::State<'_, SharedTradeEntries>,
+    channel: Option<String>,
 ) -> Result<Vec<TradeEntry>, String> {
     let trades = trade_state
         .lock()
         .map_err(|e| format!("Failed to access trade entries: {}", e))?;
-    Ok(trades.clone())
+
+    match channel {
+        Some(channel) => Ok(trades
+            .iter()
+            .filter(|entry| entry.channel.eq_ignore_ascii_case(&channel))
+            .cloned()
+            .collect()),
+        None => Ok(trades.clone()),
+    }
+}
+
+#[tauri::command]
+async fn get_trade_channels(
+    trade_state: tauri::State<'_, SharedTradeEntries>,
+) -> Result<Vec<String>, String> {
+    let trades = trade_state
+        .lock()
+        .map_err(|e| format!("Failed to access trade entries: {}", e))?;
+    Ok(trade_entries::channels(&trades))
+}
+
+#[tauri::command]
+async fn price_check(
+    price_state: tauri::State<'_, SharedPriceHistory>,
+    item: String,
+    ql: Option<f64>,
+) -> Result<PriceCheck, String> {
+    if item.trim().is_empty() {
+        return Err("Item name is required".to_string());
+    }
+
+    let history = price_state
+        .lock()
+        .map_err(|e| format!("Failed to access price history: {}", e))?;
+
+    Ok(price_history::price_check(&history, &item, ql, granger::unix_now()))
+}
+
+#[tauri::command]
+async fn get_merchant_sales(
+    sales_state: tauri::State<'_, SharedMerchantSales>,
+) -> Result<Vec<MerchantSale>, String> {
+    let sales = sales_state
+        .lock()
+        .map_err(|e| format!("Failed to access merchant sales: {}", e))?;
+    Ok(sales.clone())
+}
+
+#[tauri::command]
+async fn get_merchant_income(
+    sales_state: tauri::State<'_, SharedMerchantSales>,
+    period: IncomePeriod,
+) -> Result<Vec<IncomeBucket>, String> {
+    let sales = sales_state
+        .lock()
+        .map_err(|e| format!("Failed to access merchant sales: {}", e))?;
+    Ok(merchant_sales::income_summary(&sales, period))
+}
+
+#[tauri::command]
+async fn get_trade_profiles(
+    settings_state: tauri::State<'_, SharedSettings>,
+) -> Result<Vec<TradeProfile>, String> {
+    let watch_dir = {
+        let settings = settings_state
+            .lock()
+            .map_err(|e| format!("Failed to access settings: {}", e))?;
+        settings.watch_dir.clone()
+    };
+
+    if watch_dir.trim().is_empty() {
+        return Err("No watch directory configured".to_string());
+    }
+
+    trade_profiles::build_profiles(&watch_dir)
 }
 
 #[tauri::command]
@@ -266,21 +467,22 @@ async fn open_granger_window(
 
     let url = tauri::WebviewUrl::App("granger.html".into());
 
-    match tauri::webview::WebviewWindowBuilder::new(&app, "granger", url)
+    let builder = tauri::webview::WebviewWindowBuilder::new(&app, "granger", url)
         .title("Granger Panel")
         .inner_size(640.0, 420.0)
         .min_inner_size(460.0, 280.0)
         .resizable(true)
         .decorations(false)
-        .shadow(false)
-        .build()
-    {
+        .shadow(false);
+
+    match window_state::apply(builder, &window_state::saved(&app, "granger")).build() {
         Ok(window) => {
+            window_state::opened(&app, &window);
             let _ = window.emit("granger-entries", snapshot);
             Ok(())
         }
         Err(err) => {
-            println!("Failed to create granger window: {:?}", err);
+            warn!("Failed to create granger window: {:?}", err);
             Err(format!("Failed to create granger window: {:?}", err))
         }
     }
@@ -289,24 +491,27 @@ async fn open_granger_window(
 #[tauri::command]
 async fn open_map_window(app: tauri::AppHandle) -> Result<(), String> {
     if let Some(existing) = app.get_webview_window("map") {
-        println!("Map window already open; showing existing instance");
+        debug!("Map window already open; showing existing instance");
         let _ = existing.show();
         return Ok(());
     }
 
     let url = tauri::WebviewUrl::App("map.html".into());
 
-    match tauri::webview::WebviewWindowBuilder::new(&app, "map", url)
+    let builder = tauri::webview::WebviewWindowBuilder::new(&app, "map", url)
         .title("Wurm Panels Map")
         .inner_size(1200.0, 900.0)
         .min_inner_size(800.0, 600.0)
         .resizable(true)
-        .devtools(true)
-        .build()
-    {
-        Ok(_) => Ok(()),
+        .devtools(true);
+
+    match window_state::apply(builder, &window_state::saved(&app, "map")).build() {
+        Ok(window) => {
+            window_state::opened(&app, &window);
+            Ok(())
+        }
         Err(err) => {
-            println!("Failed to create map window: {:?}", err);
+            warn!("Failed to create map window: {:?}", err);
             Err(format!("Failed to create map window: {:?}", err))
         }
     }
@@ -322,6 +527,337 @@ async fn get_granger_entries(
     Ok(entries.values().cloned().collect())
 }
 
+#[tauri::command]
+async fn get_examine_history(
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    id: String,
+) -> Result<Vec<ExamineSnapshot>, String> {
+    let entries = granger_state
+        .lock()
+        .map_err(|e| format!("Failed to access granger entries: {}", e))?;
+    entries
+        .get(&id)
+        .map(|animal| animal.examinations.clone())
+        .ok_or_else(|| format!("Animal not found: {}", id))
+}
+
+#[tauri::command]
+async fn diff_latest_examines(
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    id: String,
+) -> Result<Option<ExamineDiff>, String> {
+    let entries = granger_state
+        .lock()
+        .map_err(|e| format!("Failed to access granger entries: {}", e))?;
+    entries
+        .get(&id)
+        .map(granger_history::diff_latest)
+        .ok_or_else(|| format!("Animal not found: {}", id))
+}
+
+/// Locks the herd for `change`, then saves the animals it touched and sends
+/// the herd to every window. Returns what `change` returned along with the
+/// updated herd.
+fn update_granger<T>(
+    app: &tauri::AppHandle,
+    granger_state: &SharedGrangerEntries,
+    change: impl FnOnce(&mut BTreeMap<String, GrangerAnimal>) -> Result<T, String>,
+) -> Result<(T, Vec<GrangerAnimal>), String> {
+    let mut entries = granger_state
+        .lock()
+        .map_err(|e| format!("Failed to access granger entries: {}", e))?;
+
+    let before = entries.clone();
+    let result = change(&mut entries)?;
+    granger::persist_animals(&entries, &granger::changed_ids(&before, &entries))?;
+    let snapshot = granger::to_vec(&entries);
+    drop(entries);
+
+    granger::broadcast(app, snapshot.clone());
+    Ok((result, snapshot))
+}
+
+#[tauri::command]
+async fn merge_granger_animals(
+    app: tauri::AppHandle,
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    payload: MergeGrangerAnimalsPayload,
+) -> Result<Vec<GrangerAnimal>, String> {
+    let ((), snapshot) = update_granger(&app, &granger_state, |entries| {
+        granger::merge_animals(entries, &payload.source_id, &payload.target_id)
+    })?;
+    Ok(snapshot)
+}
+
+#[tauri::command]
+async fn split_granger_animal(
+    app: tauri::AppHandle,
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    payload: SplitGrangerAnimalPayload,
+) -> Result<Vec<GrangerAnimal>, String> {
+    let ((), snapshot) = update_granger(&app, &granger_state, |entries| {
+        granger::split_animal(entries, &payload.id)
+    })?;
+    Ok(snapshot)
+}
+
+#[tauri::command]
+async fn delete_granger_animal(
+    app: tauri::AppHandle,
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    payload: GrangerAnimalPayload,
+) -> Result<Vec<GrangerAnimal>, String> {
+    let (removed, snapshot) = update_granger(&app, &granger_state, |entries| {
+        granger::delete_animal(entries, &payload.id)
+    })?;
+    info!("Deleted granger animal {} ({})", removed.name, removed.id);
+    Ok(snapshot)
+}
+
+#[tauri::command]
+async fn archive_granger_animal(
+    app: tauri::AppHandle,
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    payload: ArchiveGrangerAnimalPayload,
+) -> Result<Vec<GrangerAnimal>, String> {
+    let ((), snapshot) = update_granger(&app, &granger_state, |entries| {
+        granger::set_archived(entries, &payload.id, payload.archived)
+    })?;
+    Ok(snapshot)
+}
+
+#[tauri::command]
+async fn edit_granger_animal(
+    app: tauri::AppHandle,
+    settings_state: tauri::State<'_, SharedSettings>,
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    payload: EditGrangerAnimalPayload,
+) -> Result<Vec<GrangerAnimal>, String> {
+    let weights = {
+        let settings = settings_state
+            .lock()
+            .map_err(|e| format!("Failed to access settings: {}", e))?;
+        settings.trait_weights.clone()
+    };
+
+    let ((), snapshot) = update_granger(&app, &granger_state, |entries| {
+        granger::edit_animal(entries, &payload.id, payload.edit, &weights)
+    })?;
+    Ok(snapshot)
+}
+
+#[tauri::command]
+async fn set_granger_tags(
+    app: tauri::AppHandle,
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    payload: GrangerTagsPayload,
+) -> Result<Vec<GrangerAnimal>, String> {
+    let ((), snapshot) = update_granger(&app, &granger_state, |entries| {
+        granger::set_tags(entries, &payload.id, payload.tags)
+    })?;
+    Ok(snapshot)
+}
+
+#[tauri::command]
+async fn set_granger_notes(
+    app: tauri::AppHandle,
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    payload: GrangerNotesPayload,
+) -> Result<Vec<GrangerAnimal>, String> {
+    let ((), snapshot) = update_granger(&app, &granger_state, |entries| {
+        granger::set_notes(entries, &payload.id, payload.notes)
+    })?;
+    Ok(snapshot)
+}
+
+#[tauri::command]
+async fn export_granger_entries(
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    payload: ExportGrangerPayload,
+) -> Result<usize, String> {
+    let format = payload
+        .format
+        .unwrap_or_else(|| ExchangeFormat::from_path(&payload.path));
+    let entries = granger_state
+        .lock()
+        .map_err(|e| format!("Failed to access granger entries: {}", e))?;
+
+    let count = granger_exchange::export(&entries, &payload.filter, format, &payload.path)?;
+    info!("Exported {} granger animals to {}", count, payload.path);
+    Ok(count)
+}
+
+#[tauri::command]
+async fn import_granger_entries(
+    app: tauri::AppHandle,
+    settings_state: tauri::State<'_, SharedSettings>,
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    payload: ImportGrangerPayload,
+) -> Result<ImportReport, String> {
+    let format = payload
+        .format
+        .unwrap_or_else(|| ExchangeFormat::from_path(&payload.path));
+    let weights = {
+        let settings = settings_state
+            .lock()
+            .map_err(|e| format!("Failed to access settings: {}", e))?;
+        settings.trait_weights.clone()
+    };
+
+    let (report, _) = update_granger(&app, &granger_state, |entries| {
+        granger_exchange::import(entries, &payload.path, format, &weights)
+    })?;
+    info!(
+        "Imported granger data from {}: {} added, {} updated, {} conflicts",
+        payload.path,
+        report.added,
+        report.updated,
+        report.conflicts.len()
+    );
+    Ok(report)
+}
+
+#[tauri::command]
+async fn get_upcoming_births(
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    within_days: Option<f64>,
+) -> Result<Vec<UpcomingBirth>, String> {
+    let entries = granger_state
+        .lock()
+        .map_err(|e| format!("Failed to access granger entries: {}", e))?;
+    Ok(granger_births::upcoming_births(
+        &entries,
+        granger::unix_now(),
+        within_days,
+    ))
+}
+
+#[tauri::command]
+async fn granger_stats(
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    stale_after_days: Option<f64>,
+) -> Result<HerdStats, String> {
+    let entries = granger_state
+        .lock()
+        .map_err(|e| format!("Failed to access granger entries: {}", e))?;
+    Ok(granger_stats::herd_stats(
+        &entries,
+        granger::unix_now(),
+        stale_after_days.unwrap_or(granger_stats::DEFAULT_STALE_AFTER_DAYS),
+    ))
+}
+
+#[tauri::command]
+async fn get_granger_ancestors(
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    payload: PedigreeQueryPayload,
+) -> Result<Vec<PedigreeRelative>, String> {
+    let entries = granger_state
+        .lock()
+        .map_err(|e| format!("Failed to access granger entries: {}", e))?;
+    granger_pedigree::ancestors(
+        &entries,
+        &payload.id,
+        payload
+            .generations
+            .unwrap_or(granger_pedigree::DEFAULT_GENERATIONS),
+    )
+}
+
+#[tauri::command]
+async fn get_granger_descendants(
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    payload: PedigreeQueryPayload,
+) -> Result<Vec<PedigreeRelative>, String> {
+    let entries = granger_state
+        .lock()
+        .map_err(|e| format!("Failed to access granger entries: {}", e))?;
+    granger_pedigree::descendants(
+        &entries,
+        &payload.id,
+        payload
+            .generations
+            .unwrap_or(granger_pedigree::DEFAULT_GENERATIONS),
+    )
+}
+
+#[tauri::command]
+async fn export_granger_lineage(
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    payload: ExportLineagePayload,
+) -> Result<LineageNode, String> {
+    let lineage = {
+        let entries = granger_state
+            .lock()
+            .map_err(|e| format!("Failed to access granger entries: {}", e))?;
+        granger_pedigree::lineage(
+            &entries,
+            &payload.id,
+            payload
+                .generations
+                .unwrap_or(granger_pedigree::DEFAULT_GENERATIONS),
+        )?
+    };
+
+    if let Some(path) = &payload.path {
+        granger_pedigree::export_lineage(&lineage, path)?;
+        info!("Exported lineage for {} to {}", payload.id, path);
+    }
+    Ok(lineage)
+}
+
+#[tauri::command]
+async fn get_trait_catalogue(
+    settings_state: tauri::State<'_, SharedSettings>,
+) -> Result<Vec<TraitCatalogueEntry>, String> {
+    let settings = settings_state
+        .lock()
+        .map_err(|e| format!("Failed to access settings: {}", e))?;
+    Ok(granger_traits::catalogue(&settings.trait_weights))
+}
+
+#[tauri::command]
+async fn update_trait_weights(
+    app: tauri::AppHandle,
+    settings_state: tauri::State<'_, SharedSettings>,
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    weights: TraitWeights,
+) -> Result<Vec<TraitCatalogueEntry>, String> {
+    let mut settings = settings_state
+        .lock()
+        .map_err(|e| format!("Failed to access settings: {}", e))?;
+    settings.trait_weights = weights.clone();
+    let updated = settings.clone();
+    drop(settings);
+
+    persist_settings(&updated)?;
+
+    update_granger(&app, &granger_state, |entries| {
+        granger::rescore(entries, &weights);
+        Ok(())
+    })?;
+    Ok(granger_traits::catalogue(&weights))
+}
+
+#[tauri::command]
+async fn plan_breeding_pairs(
+    settings_state: tauri::State<'_, SharedSettings>,
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    payload: PairingRequest,
+) -> Result<Vec<PairingSuggestion>, String> {
+    let weights = {
+        let settings = settings_state
+            .lock()
+            .map_err(|e| format!("Failed to access settings: {}", e))?;
+        settings.trait_weights.clone()
+    };
+
+    let entries = granger_state
+        .lock()
+        .map_err(|e| format!("Failed to access granger entries: {}", e))?;
+    Ok(granger_planner::plan_pairings(&entries, &weights, &payload))
+}
+
 #[tauri::command]
 async fn update_window_title(
     app: tauri::AppHandle,
@@ -339,26 +875,27 @@ async fn update_window_title(
 #[tauri::command]
 async fn open_watcher_window(app: tauri::AppHandle) -> Result<(), String> {
     if let Some(existing) = app.get_webview_window("watcher") {
-        println!("Watcher window already open; showing existing instance");
+        debug!("Watcher window already open; showing existing instance");
         let _ = existing.show();
         return Ok(());
     }
 
     let url = tauri::WebviewUrl::App("watcher.html".into());
 
-    match tauri::webview::WebviewWindowBuilder::new(&app, "watcher", url)
+    let builder = tauri::webview::WebviewWindowBuilder::new(&app, "watcher", url)
         .title("Wurm Watcher")
         .inner_size(400.0, 600.0)
         .resizable(true)
-        .decorations(true)
-        .build()
-    {
-        Ok(_) => {
-            println!("Watcher window created successfully");
+        .decorations(true);
+
+    match window_state::apply(builder, &window_state::saved(&app, "watcher")).build() {
+        Ok(window) => {
+            debug!("Watcher window created successfully");
+            window_state::opened(&app, &window);
             Ok(())
         }
         Err(e) => {
-            println!("Failed to create watcher window: {:?}", e);
+            warn!("Failed to create watcher window: {:?}", e);
             Err(format!("Failed to create watcher window: {:?}", e))
         }
     }
@@ -401,7 +938,74 @@ async fn set_always_on_top(
 
     window
         .set_always_on_top(payload.always_on_top)
-        .map_err(|err| format!("Failed to set always_on_top: {err}"))
+        .map_err(|err| format!("Failed to set always_on_top: {err}"))?;
+
+    window_state::update(&app, &payload.window_label, |state| {
+        state.always_on_top = Some(payload.always_on_top);
+    });
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_window_opacity(
+    app: tauri::AppHandle,
+    payload: SetWindowOpacityPayload,
+) -> Result<f64, String> {
+    let opacity = payload.opacity.clamp(window_state::MIN_OPACITY, 1.0);
+    window_state::update(&app, &payload.window_label, |state| {
+        state.opacity = Some(opacity);
+    });
+
+    // The panel fades itself; there is no native opacity on every platform.
+    if let Some(window) = app.get_webview_window(&payload.window_label) {
+        if let Err(err) = window.emit_to(payload.window_label.as_str(), "window-opacity", opacity) {
+            warn!(
+                "Failed to send opacity to {}: {:?}",
+                payload.window_label, err
+            );
+        }
+    }
+    Ok(opacity)
+}
+
+#[tauri::command]
+async fn get_window_state(
+    app: tauri::AppHandle,
+    window_label: String,
+) -> Result<WindowState, String> {
+    Ok(window_state::saved(&app, &window_label))
+}
+
+/// Opens a panel by its window label, used to bring back the panels that
+/// were open when the app last ran.
+async fn open_window_by_label(app: tauri::AppHandle, label: &str) -> Result<(), String> {
+    match label {
+        "skills" => open_skills_window(app.clone(), app.state()).await,
+        "farming-grind" => open_farming_grind_window(app).await,
+        "settings" => open_settings_window(app.clone(), app.state()).await,
+        "trade" => open_trade_window(app.clone(), app.state()).await,
+        "granger" => open_granger_window(app.clone(), app.state()).await,
+        "map" => open_map_window(app).await,
+        "watcher" => open_watcher_window(app).await,
+        other => Err(format!("Unknown window: {}", other)),
+    }
+}
+
+/// Reapplies the saved layout to open panels and opens the ones it lists as
+/// open, e.g. after a profile switch.
+fn reapply_window_state(app: &tauri::AppHandle) {
+    let to_open = window_state::reapply(app);
+    if to_open.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    async_runtime::spawn(async move {
+        for label in to_open {
+            if let Err(err) = open_window_by_label(app.clone(), &label).await {
+                warn!("Failed to open {} window: {}", label, err);
+            }
+        }
+    });
 }
 
 #[tauri::command]
@@ -414,48 +1018,243 @@ async fn close_granger_window(app: tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Returns the startup settings notice once, so it is only shown to the user
+/// a single time. The main window takes it as soon as it loads.
+#[tauri::command]
+async fn take_settings_notice(
+    notice_state: tauri::State<'_, SharedSettingsNotice>,
+) -> Result<Option<SettingsNotice>, String> {
+    let mut notice = notice_state
+        .lock()
+        .map_err(|e| format!("Failed to access settings notice: {}", e))?;
+    Ok(notice.take())
+}
+
+#[tauri::command]
+async fn discover_log_directories() -> Result<LogDirectoryDiscovery, String> {
+    let discovery = log_discovery::discover();
+    info!(
+        "Found {} candidate log directories",
+        discovery.candidates.len()
+    );
+    Ok(discovery)
+}
+
 #[tauri::command]
 async fn update_settings(
     app: tauri::AppHandle,
     settings_state: tauri::State<'_, SharedSettings>,
     payload: UpdateSettingsPayload,
+) -> Result<SettingsUpdate, String> {
+    let diagnostics = payload
+        .watch_dir
+        .as_deref()
+        .map(|watch_dir| log_discovery::diagnose(watch_dir.trim(), granger::unix_now()));
+
+    let mut settings = settings_state
+        .lock()
+        .map_err(|e| format!("Failed to access settings: {}", e))?;
+
+    let rejected = diagnostics
+        .as_ref()
+        .is_some_and(|diagnostics| diagnostics.level == DiagnosticLevel::Error);
+    if rejected && !payload.force {
+        return Ok(SettingsUpdate {
+            saved: false,
+            settings: settings.clone(),
+            watch_dir: diagnostics,
+        });
+    }
+
+    let switched = match payload.profile.as_deref().map(str::trim) {
+        Some("") => return Err("Profile name cannot be empty".to_string()),
+        Some(name) => settings.switch_profile(name),
+        None => false,
+    };
+    if let Some(watch_dir) = payload.watch_dir {
+        settings.watch_dir = watch_dir.trim().to_string();
+    }
+    if let Some(trade_watchlist) = payload.trade_watchlist {
+        settings.trade_watchlist = trade_watchlist;
+    }
+    if let Some(skill_targets) = payload.skill_targets {
+        settings.skill_targets = skill_targets;
+    }
+    if let Some(auto_switch_profiles) = payload.auto_switch_profiles {
+        settings.auto_switch_profiles = auto_switch_profiles;
+    }
+    settings.stash_profile();
+    let updated = settings.clone();
+    drop(settings);
+
+    persist_settings(&updated)?;
+    app_settings::broadcast(&app, &updated);
+    if switched {
+        info!("Switched to settings profile {}", updated.active_profile);
+        // The watcher follows the new watch dir on its next poll.
+        reapply_window_state(&app);
+    }
+
+    Ok(SettingsUpdate {
+        saved: true,
+        settings: updated,
+        watch_dir: diagnostics,
+    })
+}
+
+#[tauri::command]
+async fn validate_watch_dir(path: String) -> Result<WatchDirDiagnostics, String> {
+    Ok(log_discovery::diagnose(path.trim(), granger::unix_now()))
+}
+
+#[tauri::command]
+async fn delete_settings_profile(
+    app: tauri::AppHandle,
+    settings_state: tauri::State<'_, SharedSettings>,
+    name: String,
 ) -> Result<AppSettings, String> {
     let mut settings = settings_state
         .lock()
         .map_err(|e| format!("Failed to access settings: {}", e))?;
 
-    settings.watch_dir = payload.watch_dir.trim().to_string();
+    settings.delete_profile(&name)?;
     let updated = settings.clone();
     drop(settings);
 
     persist_settings(&updated)?;
+    app_settings::broadcast(&app, &updated);
+    Ok(updated)
+}
 
-    if let Err(err) = app.emit("settings-updated", updated.clone()) {
-        println!("Failed to emit settings update to main window: {:?}", err);
-    }
+#[tauri::command]
+async fn set_log_filter(
+    app: tauri::AppHandle,
+    settings_state: tauri::State<'_, SharedSettings>,
+    filter: String,
+) -> Result<AppSettings, String> {
+    let filter = filter.trim().to_string();
+    logging::parse_filter(&filter)?;
 
-    if let Some(watcher_window) = app.get_webview_window("watcher") {
-        if let Err(err) = watcher_window.emit("settings-updated", updated.clone()) {
-            println!("Failed to emit settings update to watcher window: {:?}", err);
-        }
-    }
+    let mut settings = settings_state
+        .lock()
+        .map_err(|e| format!("Failed to access settings: {}", e))?;
+    settings.log_filter = filter;
+    let updated = settings.clone();
+    drop(settings);
 
-    if let Some(settings_window) = app.get_webview_window("settings") {
-        if let Err(err) = settings_window.emit("settings-data", updated.clone()) {
-            println!(
-                "Failed to emit settings update to settings window: {:?}",
-                err
-            );
-        }
+    persist_settings(&updated)?;
+    logging::apply_filter(&updated.log_filter)?;
+    app_settings::broadcast(&app, &updated);
+    Ok(updated)
+}
+
+#[tauri::command]
+async fn open_log_folder(app: tauri::AppHandle) -> Result<String, String> {
+    let dir = logging::log_dir_path()?;
+    let dir = dir.to_string_lossy().to_string();
+    app.opener()
+        .open_path(dir.clone(), None::<&str>)
+        .map_err(|e| format!("Failed to open log folder: {}", e))?;
+    Ok(dir)
+}
+
+/// Bundles the log files into a zip for a bug report.
+#[tauri::command]
+async fn collect_logs(path: String) -> Result<usize, String> {
+    let count = logging::collect(&path)?;
+    info!("Collected {} log files into {}", count, path);
+    Ok(count)
+}
+
+#[tauri::command]
+async fn export_backup(
+    settings_state: tauri::State<'_, SharedSettings>,
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    path: String,
+) -> Result<BackupManifest, String> {
+    let settings = settings_state
+        .lock()
+        .map_err(|e| format!("Failed to access settings: {}", e))?
+        .clone();
+    let animals = {
+        let entries = granger_state
+            .lock()
+            .map_err(|e| format!("Failed to access granger entries: {}", e))?;
+        granger::to_vec(&entries)
+    };
+
+    let manifest = backup::export(&path, &settings, &animals)?;
+    info!(
+        "Backed up {} entries to {}",
+        manifest.entries.len(),
+        path
+    );
+    Ok(manifest)
+}
+
+#[tauri::command]
+async fn inspect_backup(path: String) -> Result<BackupManifest, String> {
+    backup::inspect(&path)
+}
+
+#[tauri::command]
+async fn restore_backup(
+    app: tauri::AppHandle,
+    settings_state: tauri::State<'_, SharedSettings>,
+    granger_state: tauri::State<'_, SharedGrangerEntries>,
+    merchant_sales_state: tauri::State<'_, SharedMerchantSales>,
+    price_history_state: tauri::State<'_, SharedPriceHistory>,
+    payload: RestoreBackupPayload,
+) -> Result<RestoreReport, String> {
+    let mut settings = settings_state
+        .lock()
+        .map_err(|e| format!("Failed to access settings: {}", e))?
+        .clone();
+    let mut entries = granger_state
+        .lock()
+        .map_err(|e| format!("Failed to access granger entries: {}", e))?;
+
+    let report = backup::restore(&payload.path, payload.mode, &mut settings, &mut entries)?;
+    let snapshot = granger::to_vec(&entries);
+    drop(entries);
+
+    if report.settings_restored {
+        *settings_state
+            .lock()
+            .map_err(|e| format!("Failed to access settings: {}", e))? = settings.clone();
+        app_settings::broadcast(&app, &settings);
+        reapply_window_state(&app);
     }
 
-    Ok(updated)
+    // Data files were rewritten underneath these stores.
+    *merchant_sales_state
+        .lock()
+        .map_err(|e| format!("Failed to access merchant sales: {}", e))? =
+        load_merchant_sales_from_disk();
+    *price_history_state
+        .lock()
+        .map_err(|e| format!("Failed to access price history: {}", e))? =
+        load_price_history_from_disk();
+
+    info!(
+        "Restored backup from {}: {} granger added, {} map layer sets, {} data files, {} removed",
+        payload.path,
+        report.granger.added,
+        report.user_layer_maps,
+        report.data_files,
+        report.removed_files
+    );
+    granger::broadcast(&app, snapshot);
+    Ok(report)
 }
 
 fn main() {
-    // Log any panic to stderr so crashes surface in the dev console.
+    logging::init();
+
+    // Log any panic through tracing, so crashes land in the rotating log file
+    // as well as on stdout for the dev console.
     std::panic::set_hook(Box::new(|info| {
-        eprintln!("Panic: {info}");
+        error!("Panic: {info}");
     }));
 
     let skill_sessions = new_skill_session_store();
@@ -464,12 +1263,25 @@ fn main() {
     let trade_entries = new_trade_store();
     let trade_entries_for_thread = Arc::clone(&trade_entries);
 
+    let price_history = new_price_history_store_with(load_price_history_from_disk());
+    let price_history_for_thread = Arc::clone(&price_history);
+
+    let merchant_sales = new_merchant_sales_store_with(load_merchant_sales_from_disk());
+    let merchant_sales_for_thread = Arc::clone(&merchant_sales);
+
     let granger_entries = new_granger_store_with(load_granger_from_disk());
     let granger_entries_for_thread = Arc::clone(&granger_entries);
 
-    let settings = new_settings_store(load_settings_from_disk());
+    let loaded_settings = load_settings_from_disk();
+    if let Err(err) = logging::apply_filter(&loaded_settings.settings.log_filter) {
+        warn!("{}; keeping default log levels", err);
+    }
+    let settings = new_settings_store(loaded_settings.settings);
+    let settings_notice: SharedSettingsNotice = Arc::new(Mutex::new(loaded_settings.notice));
+    if let (Ok(current), Ok(mut entries)) = (settings.lock(), granger_entries.lock()) {
+        granger::rescore(&mut entries, &current.trait_weights);
+    }
     let settings_for_thread = Arc::clone(&settings);
-    let settings_for_events = Arc::clone(&settings);
 
     let auth_api_base =
         env::var("AUTH_API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
@@ -483,10 +1295,14 @@ fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
         .manage(Arc::clone(&skill_sessions))
         .manage(Arc::clone(&trade_entries))
+        .manage(Arc::clone(&price_history))
+        .manage(Arc::clone(&merchant_sales))
         .manage(Arc::clone(&granger_entries))
         .manage(Arc::clone(&settings))
+        .manage(Arc::clone(&settings_notice))
         .manage(auth_client)
         .invoke_handler(tauri::generate_handler![
             open_skills_window,
@@ -498,12 +1314,48 @@ fn main() {
             get_settings,
             get_skill_sessions,
             get_trade_entries,
+            get_trade_channels,
+            price_check,
+            get_trade_profiles,
+            get_merchant_sales,
+            get_merchant_income,
             get_granger_entries,
+            get_examine_history,
+            diff_latest_examines,
+            merge_granger_animals,
+            split_granger_animal,
+            delete_granger_animal,
+            archive_granger_animal,
+            edit_granger_animal,
+            set_granger_tags,
+            set_granger_notes,
+            export_granger_entries,
+            import_granger_entries,
+            get_upcoming_births,
+            granger_stats,
+            get_granger_ancestors,
+            get_granger_descendants,
+            export_granger_lineage,
+            get_trait_catalogue,
+            update_trait_weights,
+            plan_breeding_pairs,
             close_granger_window,
             close_watcher_window,
             verify_session,
             update_settings,
+            delete_settings_profile,
+            discover_log_directories,
+            validate_watch_dir,
+            export_backup,
+            inspect_backup,
+            restore_backup,
+            set_log_filter,
+            open_log_folder,
+            collect_logs,
+            take_settings_notice,
             set_always_on_top,
+            set_window_opacity,
+            get_window_state,
             update_window_title,
             user_layers_persistence::load_user_layers,
             user_layers_persistence::save_user_layers,
@@ -525,35 +1377,21 @@ fn main() {
         ])
         .on_window_event(move |window, event| {
             use tauri::WindowEvent;
-            if window.label() == "skills" {
+            if window_state::PANEL_LABELS.contains(&window.label()) {
                 match event {
                     WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
-                        let (Ok(pos), Ok(size)) = (window.outer_position(), window.outer_size())
-                        else {
-                            println!("Failed to read skills window geometry");
-                            return;
-                        };
-
-                        let settings_store = Arc::clone(&settings_for_events);
-
-                        if let Ok(mut settings) = settings_store.lock() {
-                            settings.skills_window.x = pos.x as f64;
-                            settings.skills_window.y = pos.y as f64;
-                            settings.skills_window.width = size.width as f64;
-                            settings.skills_window.height = size.height as f64;
-
-                            if let Err(err) = persist_settings(&settings) {
-                                println!("Failed to persist skills window placement: {err}");
-                            }
-                        } else {
-                            println!("Failed to lock settings for window placement update");
-                        };
+                        window_state::record_geometry(window.app_handle(), window);
+                    }
+                    WindowEvent::CloseRequested { .. } => {
+                        window_state::update(window.app_handle(), window.label(), |state| {
+                            state.visible = false;
+                        });
                     }
                     _ => {}
                 }
             }
             if let WindowEvent::Destroyed = event {
-                println!("Window destroyed: {}", window.label());
+                debug!("Window destroyed: {}", window.label());
             }
         })
         .setup(move |app: &mut tauri::App| {
@@ -645,26 +1483,22 @@ fn main() {
                 .icon(tray_image)
                 .menu(&tray_menu)
                 .on_menu_event(move |app, event| {
-                    println!("Tray menu clicked: {:?}", event.id());
+                    debug!("Tray menu clicked: {:?}", event.id());
                     if event.id() == &id_open_skills {
                         let handle_for_state = app.clone();
                         async_runtime::spawn(async move {
                             let skill_state: tauri::State<SharedSkillSessions> =
                                 handle_for_state.state();
-                            let settings_state: tauri::State<SharedSettings> =
-                                handle_for_state.state();
                             let handle = handle_for_state.clone();
-                            if let Err(err) =
-                                open_skills_window(handle, skill_state, settings_state).await
-                            {
-                                println!("failed to open skills window: {}", err);
+                            if let Err(err) = open_skills_window(handle, skill_state).await {
+                                warn!("failed to open skills window: {}", err);
                             }
                         });                    } else if event.id() == &id_open_farming_grind {
                         let handle = app.clone();
                         async_runtime::spawn(async move {
                             let handle = handle.clone();
                             if let Err(err) = open_farming_grind_window(handle).await {
-                                println!("failed to open farming grind window: {}", err);
+                                warn!("failed to open farming grind window: {}", err);
                             }
                         });                     } else if event.id() == &id_open_trade {
                         let handle_for_state = app.clone();
@@ -672,7 +1506,7 @@ fn main() {
                             let state: tauri::State<SharedTradeEntries> = handle_for_state.state();
                             let handle = handle_for_state.clone();
                             if let Err(err) = open_trade_window(handle, state).await {
-                                println!("failed to open trade window: {}", err);
+                                warn!("failed to open trade window: {}", err);
                             }
                         });
                     } else if event.id() == &id_open_granger {
@@ -681,21 +1515,21 @@ fn main() {
                             let state: tauri::State<SharedGrangerEntries> = handle_for_state.state();
                             let handle = handle_for_state.clone();
                             if let Err(err) = open_granger_window(handle, state).await {
-                                println!("failed to open granger window: {}", err);
+                                warn!("failed to open granger window: {}", err);
                             }
                         });
                     } else if event.id() == &id_open_watcher {
                         let handle = app.clone();
                         async_runtime::spawn(async move {
                             if let Err(err) = open_watcher_window(handle).await {
-                                println!("failed to open watcher window: {}", err);
+                                warn!("failed to open watcher window: {}", err);
                             }
                         });
                     } else if event.id() == &id_open_map {
                         let handle = app.clone();
                         async_runtime::spawn(async move {
                             if let Err(err) = open_map_window(handle).await {
-                                println!("failed to open map window: {}", err);
+                                warn!("failed to open map window: {}", err);
                             }
                         });
                     } else if event.id() == &id_open_settings {
@@ -704,7 +1538,7 @@ fn main() {
                             let state: tauri::State<SharedSettings> = handle_for_state.state();
                             let handle = handle_for_state.clone();
                             if let Err(err) = open_settings_window(handle, state).await {
-                                println!("failed to open settings window: {}", err);
+                                warn!("failed to open settings window: {}", err);
                             }
                         });
                     } else if event.id() == &id_quit {
@@ -723,15 +1557,36 @@ fn main() {
                 });
             }
 
+            let restore_handle = app.handle().clone();
+            async_runtime::spawn(async move {
+                for label in window_state::visible_labels(&restore_handle) {
+                    if let Err(err) = open_window_by_label(restore_handle.clone(), &label).await {
+                        warn!("Failed to reopen {} window: {}", label, err);
+                    }
+                }
+            });
+
             DirectoryWatcher::new(
                 app.handle().clone(),
                 Arc::clone(&settings_for_thread),
                 Arc::clone(&skill_sessions_for_thread),
                 Arc::clone(&trade_entries_for_thread),
+                Arc::clone(&price_history_for_thread),
+                Arc::clone(&merchant_sales_for_thread),
                 Arc::clone(&granger_entries_for_thread),
             )
             .start();
 
+            granger_births::start_reminders(
+                app.handle().clone(),
+                Arc::clone(&granger_entries_for_thread),
+            );
+            profiles::start_auto_switch(
+                app.handle().clone(),
+                Arc::clone(&settings_for_thread),
+                reapply_window_state,
+            );
+
             Ok(())
         })
         .build(tauri::generate_context!())
@@ -0,0 +1,106 @@
+use crate::persistence;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+pub const MERCHANT_SALES_FILE_NAME: &str = "merchant_sales.json";
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MerchantSale {
+    pub merchant: String,
+    pub item: String,
+    pub buyer: Option<String>,
+    pub price_iron: u64,
+    pub timestamp: String,
+    pub sold_at: i64, // Unix timestamp
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct IncomeBucket {
+    pub period_start: i64, // Unix timestamp, UTC midnight
+    pub sale_count: usize,
+    pub total_iron: u64,
+    pub by_merchant: BTreeMap<String, u64>,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IncomePeriod {
+    Daily,
+    Weekly,
+}
+
+pub type SharedMerchantSales = Arc<Mutex<Vec<MerchantSale>>>;
+
+pub fn new_store_with(initial: Vec<MerchantSale>) -> SharedMerchantSales {
+    Arc::new(Mutex::new(initial))
+}
+
+/// Groups sales into UTC days or Monday-based weeks, newest first.
+pub fn income_summary(sales: &[MerchantSale], period: IncomePeriod) -> Vec<IncomeBucket> {
+    let mut buckets: BTreeMap<i64, IncomeBucket> = BTreeMap::new();
+
+    for sale in sales {
+        let start = period_start(sale.sold_at, period);
+        let bucket = buckets.entry(start).or_insert_with(|| IncomeBucket {
+            period_start: start,
+            sale_count: 0,
+            total_iron: 0,
+            by_merchant: BTreeMap::new(),
+        });
+        bucket.sale_count += 1;
+        bucket.total_iron += sale.price_iron;
+        *bucket.by_merchant.entry(sale.merchant.clone()).or_insert(0) += sale.price_iron;
+    }
+
+    buckets.into_values().rev().collect()
+}
+
+fn period_start(timestamp: i64, period: IncomePeriod) -> i64 {
+    let day = timestamp.div_euclid(SECONDS_PER_DAY);
+    let start_day = match period {
+        IncomePeriod::Daily => day,
+        // 1970-01-01 was a Thursday, so Monday is three days back from the epoch.
+        IncomePeriod::Weekly => day - (day + 3).rem_euclid(7),
+    };
+    start_day * SECONDS_PER_DAY
+}
+
+pub fn format_price(price_iron: u64) -> String {
+    let parts = [
+        (price_iron / 1_000_000, "g"),
+        (price_iron / 10_000 % 100, "s"),
+        (price_iron / 100 % 100, "c"),
+        (price_iron % 100, "i"),
+    ];
+
+    let formatted: Vec<String> = parts
+        .iter()
+        .filter(|(amount, _)| *amount > 0)
+        .map(|(amount, unit)| format!("{}{}", amount, unit))
+        .collect();
+
+    if formatted.is_empty() {
+        "0i".to_string()
+    } else {
+        formatted.join(" ")
+    }
+}
+
+pub fn load_from_disk() -> Vec<MerchantSale> {
+    match persistence::load::<Vec<MerchantSale>>(MERCHANT_SALES_FILE_NAME) {
+        Ok(items) => items.unwrap_or_default(),
+        Err(err) => {
+            warn!("Failed to load merchant sales: {}", err);
+            Vec::new()
+        }
+    }
+}
+
+pub fn persist(sales: &[MerchantSale]) -> Result<(), String> {
+    persistence::save(MERCHANT_SALES_FILE_NAME, sales)?;
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use directories::ProjectDirs;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+// How many previous versions of each data file are kept.
+pub const BACKUP_GENERATIONS: usize = 3;
+
+const BACKUP_DIR_NAME: &str = "backups";
+const LOCK_DIR_NAME: &str = ".locks";
+
+pub fn config_dir_path() -> Result<PathBuf, String> {
+    ProjectDirs::from("com", "WefNET", "wurm-sales")
+        .map(|dirs| dirs.config_dir().to_path_buf())
+        .ok_or_else(|| "Unable to resolve configuration directory".to_string())
+}
+
+/// Where a data file lives, whether or not it exists yet.
+pub fn data_path(file_name: &str) -> Result<PathBuf, String> {
+    Ok(config_dir_path()?.join(file_name))
+}
+
+/// Loads a JSON data file. Missing or blank files give `None`. A file that
+/// no longer parses falls back to the newest backup that does.
+pub fn load<T: DeserializeOwned>(file_name: &str) -> Result<Option<T>, String> {
+    let Some(raw) = read_raw(file_name)? else {
+        return Ok(None);
+    };
+
+    let err = match serde_json::from_str::<T>(&raw) {
+        Ok(value) => return Ok(Some(value)),
+        Err(err) => err,
+    };
+
+    let dir = config_dir_path()?;
+    for generation in 1..=BACKUP_GENERATIONS {
+        let backup = backup_path(&dir, file_name, generation);
+        let Ok(raw) = fs::read_to_string(&backup) else {
+            continue;
+        };
+        if let Ok(value) = serde_json::from_str::<T>(&raw) {
+            warn!(
+                "Failed to parse {} ({}); using backup {:?}",
+                file_name, err, backup
+            );
+            return Ok(Some(value));
+        }
+    }
+
+    Err(format!("Failed to parse {}: {}", file_name, err))
+}
+
+/// Writes a value as pretty JSON, safely replacing the previous version.
+pub fn save<T: Serialize + ?Sized>(file_name: &str, value: &T) -> Result<PathBuf, String> {
+    let serialized = serde_json::to_string_pretty(value)
+        .map_err(|err| format!("Failed to serialize {}: {}", file_name, err))?;
+    write_raw(file_name, &serialized)
+}
+
+/// Reads a data file as text under a shared lock. Missing or blank files
+/// give `None`.
+pub fn read_raw(file_name: &str) -> Result<Option<String>, String> {
+    let dir = config_dir_path()?;
+    let path = dir.join(file_name);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let lock = open_lock(&dir, file_name)?;
+    lock.lock_shared()
+        .map_err(|err| format!("Failed to lock {}: {}", file_name, err))?;
+    let raw = fs::read_to_string(&path)
+        .map_err(|err| format!("Failed to read {}: {}", file_name, err))?;
+    drop(lock);
+
+    if raw.trim().is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(raw))
+    }
+}
+
+/// Replaces a data file without ever leaving it half written. The new
+/// contents go to a temporary file that is renamed over the old one once
+/// flushed, after the old one is copied into the rolling backups.
+pub fn write_raw(file_name: &str, contents: &str) -> Result<PathBuf, String> {
+    let dir = config_dir_path()?;
+    if !dir.exists() {
+        fs::create_dir_all(&dir)
+            .map_err(|err| format!("Failed to create config directory: {}", err))?;
+    }
+
+    let path = dir.join(file_name);
+    let temp_path = dir.join(format!("{}.tmp", file_name));
+
+    let lock = open_lock(&dir, file_name)?;
+    lock.lock()
+        .map_err(|err| format!("Failed to lock {}: {}", file_name, err))?;
+
+    let mut temp = File::create(&temp_path)
+        .map_err(|err| format!("Failed to write {}: {}", file_name, err))?;
+    temp.write_all(contents.as_bytes())
+        .and_then(|_| temp.sync_all())
+        .map_err(|err| format!("Failed to write {}: {}", file_name, err))?;
+    drop(temp);
+
+    if path.exists() {
+        rotate_backups(&dir, file_name, &path);
+    }
+    fs::rename(&temp_path, &path)
+        .map_err(|err| format!("Failed to replace {}: {}", file_name, err))?;
+    drop(lock);
+
+    Ok(path)
+}
+
+/// Deletes a data file, e.g. a cache that can be fetched again. Backups are
+/// kept.
+pub fn remove(file_name: &str) -> Result<(), String> {
+    let dir = config_dir_path()?;
+    let lock = open_lock(&dir, file_name)?;
+    lock.lock()
+        .map_err(|err| format!("Failed to lock {}: {}", file_name, err))?;
+    match fs::remove_file(dir.join(file_name)) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(format!("Failed to delete {}: {}", file_name, err)),
+    }
+}
+
+fn open_lock(dir: &Path, file_name: &str) -> Result<File, String> {
+    let lock_dir = dir.join(LOCK_DIR_NAME);
+    fs::create_dir_all(&lock_dir)
+        .map_err(|err| format!("Failed to create lock directory: {}", err))?;
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_dir.join(format!("{}.lock", file_name)))
+        .map_err(|err| format!("Failed to open lock for {}: {}", file_name, err))
+}
+
+fn backup_path(dir: &Path, file_name: &str, generation: usize) -> PathBuf {
+    dir.join(BACKUP_DIR_NAME)
+        .join(format!("{}.{}", file_name, generation))
+}
+
+// Shifts backups down one generation and copies the current file in as the
+// newest. Failures only cost a backup, so they are logged and ignored.
+fn rotate_backups(dir: &Path, file_name: &str, current: &Path) {
+    if let Err(err) = fs::create_dir_all(dir.join(BACKUP_DIR_NAME)) {
+        warn!("Failed to create backup directory: {}", err);
+        return;
+    }
+
+    for generation in (1..BACKUP_GENERATIONS).rev() {
+        let from = backup_path(dir, file_name, generation);
+        if from.exists() {
+            let _ = fs::rename(&from, backup_path(dir, file_name, generation + 1));
+        }
+    }
+    if let Err(err) = fs::copy(current, backup_path(dir, file_name, 1)) {
+        warn!("Failed to back up {}: {}", file_name, err);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::persistence;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+pub const PRICE_HISTORY_FILE_NAME: &str = "price_history.json";
+
+// Samples older than this are dropped when new ones are recorded.
+const RETENTION_SECS: i64 = 26 * 7 * 24 * 60 * 60;
+
+// `price_check` only looks at asking prices from the last few weeks.
+pub const PRICE_CHECK_WEEKS: i64 = 4;
+
+// Samples within this many QL of the requested quality count as comparable.
+const QL_TOLERANCE: f64 = 10.0;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PriceSample {
+    pub item: String,
+    pub ql: Option<f64>,
+    pub price_iron: u64,
+    pub category: String,
+    pub speaker: Option<String>,
+    pub seen_at: i64, // Unix timestamp
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PriceCheck {
+    pub item: String,
+    pub ql: Option<f64>,
+    pub weeks: i64,
+    pub sample_count: usize,
+    pub median_iron: Option<u64>,
+    pub min_iron: Option<u64>,
+    pub max_iron: Option<u64>,
+    pub wts_count: usize,
+    pub wtb_count: usize,
+}
+
+pub type SharedPriceHistory = Arc<Mutex<Vec<PriceSample>>>;
+
+pub fn new_store_with(initial: Vec<PriceSample>) -> SharedPriceHistory {
+    Arc::new(Mutex::new(initial))
+}
+
+pub fn record_samples(history: &mut Vec<PriceSample>, samples: Vec<PriceSample>, now: i64) {
+    history.extend(samples);
+    history.retain(|sample| now - sample.seen_at <= RETENTION_SECS);
+}
+
+pub fn normalise_item(item: &str) -> String {
+    item.split_whitespace()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn price_check(history: &[PriceSample], item: &str, ql: Option<f64>, now: i64) -> PriceCheck {
+    let wanted = normalise_item(item);
+    let cutoff = now - PRICE_CHECK_WEEKS * 7 * 24 * 60 * 60;
+
+    let matching: Vec<&PriceSample> = history
+        .iter()
+        .filter(|sample| sample.seen_at >= cutoff)
+        .filter(|sample| sample.item == wanted)
+        .filter(|sample| match (ql, sample.ql) {
+            (Some(requested), Some(sampled)) => (requested - sampled).abs() <= QL_TOLERANCE,
+            // A sample without a QL says nothing about the requested quality.
+            (Some(_), None) => false,
+            (None, _) => true,
+        })
+        .collect();
+
+    let mut prices: Vec<u64> = matching.iter().map(|sample| sample.price_iron).collect();
+    prices.sort_unstable();
+
+    let median_iron = match prices.len() {
+        0 => None,
+        len if len % 2 == 1 => Some(prices[len / 2]),
+        len => Some((prices[len / 2 - 1] + prices[len / 2]) / 2),
+    };
+
+    PriceCheck {
+        item: wanted,
+        ql,
+        weeks: PRICE_CHECK_WEEKS,
+        sample_count: prices.len(),
+        median_iron,
+        min_iron: prices.first().copied(),
+        max_iron: prices.last().copied(),
+        wts_count: matching.iter().filter(|s| s.category == "WTS").count(),
+        wtb_count: matching.iter().filter(|s| s.category == "WTB").count(),
+    }
+}
+
+pub fn load_from_disk() -> Vec<PriceSample> {
+    match persistence::load::<Vec<PriceSample>>(PRICE_HISTORY_FILE_NAME) {
+        Ok(items) => items.unwrap_or_default(),
+        Err(err) => {
+            warn!("Failed to load price history: {}", err);
+            Vec::new()
+        }
+    }
+}
+
+pub fn persist(history: &[PriceSample]) -> Result<(), String> {
+    persistence::save(PRICE_HISTORY_FILE_NAME, history)?;
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use crate::app_settings::{self, persist_settings, AppSettings, SharedSettings};
+use crate::granger::unix_now;
+use crate::log_discovery::log_activity;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+const AUTO_SWITCH_INTERVAL: Duration = Duration::from_secs(30);
+
+// A character counts as being played if its logs were written this recently.
+const ACTIVE_WITHIN_SECS: i64 = 120;
+
+/// The profile whose character is being played, if that is not the active
+/// one. Only profiles with logs written in the last couple of minutes, and
+/// more recently than the active profile's, are considered.
+pub fn more_active_profile(settings: &AppSettings) -> Option<String> {
+    let now = unix_now();
+    let newest_log = |watch_dir: &str| {
+        if watch_dir.trim().is_empty() {
+            return None;
+        }
+        log_activity(Path::new(watch_dir)).1
+    };
+
+    let active_newest = newest_log(&settings.watch_dir);
+    settings
+        .profiles
+        .iter()
+        .filter(|(name, profile)| {
+            **name != settings.active_profile && profile.watch_dir != settings.watch_dir
+        })
+        .filter_map(|(name, profile)| newest_log(&profile.watch_dir).map(|newest| (name, newest)))
+        .filter(|(_, newest)| now - newest <= ACTIVE_WITHIN_SECS)
+        .filter(|(_, newest)| Some(*newest) > active_newest)
+        .max_by_key(|(_, newest)| *newest)
+        .map(|(name, _)| name.clone())
+}
+
+/// Checks periodically for another character's logs becoming active and
+/// switches to its profile when `auto_switch_profiles` is on. `on_switch`
+/// lays the panels out for the new profile.
+pub fn start_auto_switch(
+    app_handle: AppHandle,
+    settings: SharedSettings,
+    on_switch: fn(&AppHandle),
+) {
+    thread::spawn(move || loop {
+        thread::sleep(AUTO_SWITCH_INTERVAL);
+        auto_switch(&app_handle, &settings, on_switch);
+    });
+}
+
+fn auto_switch(app_handle: &AppHandle, settings: &SharedSettings, on_switch: fn(&AppHandle)) {
+    // Scanning log folders can be slow, so it runs on a copy without the lock.
+    let snapshot = {
+        let Ok(guard) = settings.lock() else {
+            warn!("Failed to lock settings for profile auto-switch");
+            return;
+        };
+        if !guard.auto_switch_profiles {
+            return;
+        }
+        guard.clone()
+    };
+    let Some(name) = more_active_profile(&snapshot) else {
+        return;
+    };
+
+    let updated = {
+        let Ok(mut guard) = settings.lock() else {
+            warn!("Failed to lock settings for profile auto-switch");
+            return;
+        };
+        // Skip if the user switched or changed profiles during the scan.
+        if !guard.auto_switch_profiles
+            || guard.active_profile != snapshot.active_profile
+            || !guard.profiles.contains_key(&name)
+        {
+            return;
+        }
+        info!("Logs for profile {} became active; switching", name);
+        guard.switch_profile(&name);
+        guard.clone()
+    };
+
+    if let Err(err) = persist_settings(&updated) {
+        warn!("Failed to persist profile switch: {}", err);
+    }
+    app_settings::broadcast(app_handle, &updated);
+    on_switch(app_handle);
+}
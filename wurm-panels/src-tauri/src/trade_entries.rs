@@ -1,11 +1,36 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 
+// A speaker repeating the same post within this window is treated as a repost.
+pub const REPOST_WINDOW_SECS: i64 = 30 * 60;
+
+// Buffer limits: posts kept per trade channel, and in total across channels.
+pub const MAX_ENTRIES_PER_CHANNEL: usize = 200;
+const MAX_ENTRIES: usize = 1000;
+
+// Minimum token overlap for two posts to count as near-duplicates.
+const REPOST_SIMILARITY: f64 = 0.85;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TradeItem {
+    pub name: String,
+    pub ql: Option<f64>,
+    pub price_iron: Option<u64>,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct TradeEntry {
     pub category: String,
+    pub channel: String,
     pub timestamp: String,
     pub message: String,
+    pub speaker: Option<String>,
+    pub items: Vec<TradeItem>,
+    pub repost_count: u32,
+    pub first_seen: i64, // Unix timestamp
+    pub last_seen: i64,  // Unix timestamp
+    pub last_timestamp: String,
 }
 
 pub type SharedTradeEntries = Arc<Mutex<Vec<TradeEntry>>>;
@@ -14,9 +39,90 @@ pub fn new_store() -> SharedTradeEntries {
     Arc::new(Mutex::new(Vec::new()))
 }
 
+/// Keeps at most `max` entries per channel, dropping the oldest first, so a
+/// busy server cannot push another server's posts out of the buffer. The
+/// whole buffer is also held to `MAX_ENTRIES`.
 pub fn truncate_entries(entries: &mut Vec<TradeEntry>, max: usize) {
-    if entries.len() > max {
-        let overflow = entries.len() - max;
-        entries.drain(0..overflow);
+    let mut per_channel: HashMap<String, usize> = HashMap::new();
+    for entry in entries.iter() {
+        *per_channel.entry(entry.channel.clone()).or_insert(0) += 1;
+    }
+
+    entries.retain(|entry| match per_channel.get_mut(&entry.channel) {
+        Some(remaining) if *remaining > max => {
+            *remaining -= 1;
+            false
+        }
+        _ => true,
+    });
+
+    let excess = entries.len().saturating_sub(MAX_ENTRIES);
+    entries.drain(..excess);
+}
+
+pub fn channels(entries: &[TradeEntry]) -> Vec<String> {
+    let mut channels: Vec<String> = entries.iter().map(|entry| entry.channel.clone()).collect();
+    channels.sort();
+    channels.dedup();
+    channels
+}
+
+/// Adds `entry` to the buffer, or folds it into an earlier post by the same
+/// speaker when it is a repost within `REPOST_WINDOW_SECS`. A folded post
+/// moves to the end, so truncation treats it as fresh.
+///
+/// Returns `true` when the entry was folded rather than pushed.
+pub fn record_entry(entries: &mut Vec<TradeEntry>, entry: TradeEntry) -> bool {
+    if let Some(index) = find_repost_target(entries, &entry) {
+        let mut existing = entries.remove(index);
+        existing.repost_count += 1;
+        existing.last_seen = entry.last_seen;
+        existing.last_timestamp = entry.timestamp;
+        entries.push(existing);
+        return true;
+    }
+
+    entries.push(entry);
+    false
+}
+
+fn find_repost_target(entries: &[TradeEntry], entry: &TradeEntry) -> Option<usize> {
+    let speaker = entry.speaker.as_deref()?;
+    let tokens = normalised_tokens(&entry.message, speaker);
+    if tokens.is_empty() {
+        return None;
+    }
+
+    entries.iter().rposition(|existing| {
+        existing.category == entry.category
+            && existing.channel == entry.channel
+            && existing
+                .speaker
+                .as_deref()
+                .is_some_and(|other| other.eq_ignore_ascii_case(speaker))
+            && entry.last_seen - existing.last_seen <= REPOST_WINDOW_SECS
+            && similarity(&tokens, &normalised_tokens(&existing.message, speaker))
+                >= REPOST_SIMILARITY
+    })
+}
+
+fn normalised_tokens(message: &str, speaker: &str) -> HashSet<String> {
+    let speaker_tag = format!("<{}>", speaker);
+    let body = message
+        .trim()
+        .strip_prefix(speaker_tag.as_str())
+        .unwrap_or(message);
+
+    body.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+fn similarity(left: &HashSet<String>, right: &HashSet<String>) -> f64 {
+    let union = left.union(right).count();
+    if union == 0 {
+        return 0.0;
     }
+    left.intersection(right).count() as f64 / union as f64
 }
@@ -0,0 +1,256 @@
+use crate::price_history::normalise_item;
+use crate::watcher::{
+    get_chat_type, log_channel_name, parse_trade_entry, read_file_contents, LogClock,
+};
+use chrono::{Local, TimeZone};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use tracing::warn;
+
+// Number of items listed under "usually sells" and "usually buys".
+const TOP_ITEM_COUNT: usize = 5;
+
+// How first and last seen are shown, in local time.
+const SEEN_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Clone, Serialize)]
+pub struct ItemFrequency {
+    pub item: String,
+    pub count: usize,
+}
+
+#[derive(Clone, Serialize)]
+pub struct PmLog {
+    pub path: String,
+    pub message_count: usize,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TradeProfile {
+    pub player: String,
+    pub post_count: usize,
+    pub wts_count: usize,
+    pub wtb_count: usize,
+    pub pc_count: usize,
+    pub usually_sells: Vec<ItemFrequency>,
+    pub usually_buys: Vec<ItemFrequency>,
+    pub active_days: usize,
+    pub posts_per_active_day: f64,
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+    pub channels: Vec<String>,
+    pub pm_logs: Vec<PmLog>,
+}
+
+#[derive(Default)]
+struct ProfileBuilder {
+    player: String,
+    post_count: usize,
+    wts_count: usize,
+    wtb_count: usize,
+    pc_count: usize,
+    sells: HashMap<String, usize>,
+    buys: HashMap<String, usize>,
+    days: BTreeSet<String>,
+    first_seen: Option<i64>, // Unix timestamp
+    last_seen: Option<i64>,  // Unix timestamp
+    channels: BTreeSet<String>,
+}
+
+/// Builds a profile for every speaker found in the trade logs of `watch_dir`,
+/// most active first.
+pub fn build_profiles(watch_dir: &str) -> Result<Vec<TradeProfile>, String> {
+    let entries = fs::read_dir(watch_dir)
+        .map_err(|err| format!("Failed to read watch directory {}: {}", watch_dir, err))?;
+
+    let mut trade_logs = Vec::new();
+    let mut pm_logs: HashMap<String, Vec<PmLog>> = HashMap::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let path_str = path.to_string_lossy().to_string();
+        match get_chat_type(&path_str).as_str() {
+            "Trade" => trade_logs.push(path),
+            "PM" => {
+                if let Some(player) = pm_partner(&path) {
+                    let message_count = read_file_contents(&path)
+                        .map(|content| count_messages(&content))
+                        .unwrap_or(0);
+                    pm_logs
+                        .entry(player.to_ascii_lowercase())
+                        .or_default()
+                        .push(PmLog {
+                            path: path_str,
+                            message_count,
+                        });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Channels log to separate files, so read order says nothing about time;
+    // first and last seen come from when each line was logged.
+    trade_logs.sort();
+
+    let mut builders: HashMap<String, ProfileBuilder> = HashMap::new();
+    for path in &trade_logs {
+        let content = match read_file_contents(path) {
+            Ok(content) => content,
+            Err(err) => {
+                warn!("Failed to read trade log {:?}: {}", path, err);
+                continue;
+            }
+        };
+        let channel = log_channel_name(&path.to_string_lossy());
+        absorb_trade_log(&content, &channel, &mut builders);
+    }
+
+    let mut profiles: Vec<TradeProfile> = builders
+        .into_iter()
+        .map(|(key, builder)| {
+            let pm_logs = pm_logs.remove(&key).unwrap_or_default();
+            builder.finish(pm_logs)
+        })
+        .collect();
+
+    profiles.sort_by(|a, b| {
+        b.post_count
+            .cmp(&a.post_count)
+            .then_with(|| a.player.cmp(&b.player))
+    });
+
+    Ok(profiles)
+}
+
+fn absorb_trade_log(content: &str, channel: &str, builders: &mut HashMap<String, ProfileBuilder>) {
+    let mut clock = LogClock::default();
+
+    for line in content.lines() {
+        let line = line.trim();
+        let logged_at = clock.observe(line);
+
+        let Some(entry) = parse_trade_entry(line, channel, logged_at.unwrap_or_default()) else {
+            continue;
+        };
+        let Some(speaker) = entry.speaker.clone() else {
+            continue;
+        };
+
+        let builder = builders
+            .entry(speaker.to_ascii_lowercase())
+            .or_insert_with(|| ProfileBuilder {
+                player: speaker.clone(),
+                ..ProfileBuilder::default()
+            });
+
+        builder.post_count += 1;
+        builder.channels.insert(entry.channel.clone());
+        let wanted = match entry.category.as_str() {
+            "WTS" => {
+                builder.wts_count += 1;
+                Some(&mut builder.sells)
+            }
+            "WTB" => {
+                builder.wtb_count += 1;
+                Some(&mut builder.buys)
+            }
+            "PC" => {
+                builder.pc_count += 1;
+                None
+            }
+            _ => None,
+        };
+        if let Some(counts) = wanted {
+            for item in &entry.items {
+                *counts.entry(normalise_item(&item.name)).or_insert(0) += 1;
+            }
+        }
+
+        // Lines from a file without a "Logging started" date cannot be placed.
+        let Some(logged_at) = logged_at else {
+            continue;
+        };
+        if let Some(day) = format_local(logged_at, "%Y-%m-%d") {
+            builder.days.insert(day);
+        }
+        builder.first_seen = Some(
+            builder
+                .first_seen
+                .map_or(logged_at, |seen| seen.min(logged_at)),
+        );
+        builder.last_seen = Some(
+            builder
+                .last_seen
+                .map_or(logged_at, |seen| seen.max(logged_at)),
+        );
+    }
+}
+
+impl ProfileBuilder {
+    fn finish(self, pm_logs: Vec<PmLog>) -> TradeProfile {
+        let active_days = self.days.len();
+        let posts_per_active_day = if active_days == 0 {
+            0.0
+        } else {
+            self.post_count as f64 / active_days as f64
+        };
+
+        TradeProfile {
+            player: self.player,
+            post_count: self.post_count,
+            wts_count: self.wts_count,
+            wtb_count: self.wtb_count,
+            pc_count: self.pc_count,
+            usually_sells: top_items(self.sells),
+            usually_buys: top_items(self.buys),
+            active_days,
+            posts_per_active_day,
+            first_seen: self.first_seen.and_then(|at| format_local(at, SEEN_FORMAT)),
+            last_seen: self.last_seen.and_then(|at| format_local(at, SEEN_FORMAT)),
+            channels: self.channels.into_iter().collect(),
+            pm_logs,
+        }
+    }
+}
+
+fn top_items(counts: HashMap<String, usize>) -> Vec<ItemFrequency> {
+    let mut items: Vec<ItemFrequency> = counts
+        .into_iter()
+        .map(|(item, count)| ItemFrequency { item, count })
+        .collect();
+    items.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.item.cmp(&b.item)));
+    items.truncate(TOP_ITEM_COUNT);
+    items
+}
+
+fn format_local(timestamp: i64, format: &str) -> Option<String> {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|at| at.format(format).to_string())
+}
+
+fn pm_partner(path: &Path) -> Option<String> {
+    let file_name = path.file_name()?.to_str()?;
+    let after = &file_name[file_name.find("PM__")? + "PM__".len()..];
+    let name = after.split('.').next()?.trim();
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
+fn count_messages(content: &str) -> usize {
+    content
+        .lines()
+        .filter(|line| line.trim_start().starts_with('['))
+        .count()
+}
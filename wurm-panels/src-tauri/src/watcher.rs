@@ -1,22 +1,114 @@
 use crate::app_settings::SharedSettings;
 use crate::granger::{
-    persist as persist_granger, to_vec as granger_to_vec, GrangerAnimal, SharedGrangerEntries,
+    broadcast as broadcast_granger, persist_animals as persist_granger_animals, record_examine,
+    sex_from_sentence, to_vec as granger_to_vec, GrangerAnimal, SharedGrangerEntries,
+};
+use crate::granger_traits::{
+    breeding_score, classify_sentence, label as trait_label, CreatureTrait, TraitWeights,
+};
+use crate::merchant_sales::{
+    format_price, persist as persist_merchant_sales, MerchantSale, SharedMerchantSales,
+};
+use crate::price_history::{
+    normalise_item, persist as persist_price_history, record_samples, PriceSample,
+    SharedPriceHistory,
 };
 use crate::skill_sessions::{SharedSkillSessions, SkillSessionData};
-use crate::trade_entries::{truncate_entries, SharedTradeEntries, TradeEntry};
+use crate::trade_entries::{
+    record_entry, truncate_entries, SharedTradeEntries, TradeEntry, TradeItem,
+    MAX_ENTRIES_PER_CHANNEL,
+};
+use chrono::{Local, NaiveDate, NaiveTime, TimeZone};
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+use tracing::{debug, info, warn};
+
+// Price samples are written out at most this often, so a busy trade channel
+// does not rewrite the whole history file on every line.
+const PRICE_HISTORY_SAVE_DELAY: Duration = Duration::from_secs(30);
+
+/// Stores changed during a scan that are written out later in one go.
+#[derive(Default)]
+struct PendingWrites {
+    price_history_since: Option<Instant>,
+    merchant_sales: Vec<MerchantSale>,
+}
+
+impl PendingWrites {
+    // Saves, announces and notifies the scan's merchant sales together, so a
+    // catch-up burst gives one notification rather than one per line.
+    fn flush_merchant_sales(&mut self, app_handle: &AppHandle, store: &SharedMerchantSales) {
+        if self.merchant_sales.is_empty() {
+            return;
+        }
+        let new_sales = std::mem::take(&mut self.merchant_sales);
+
+        let (title, body) = match new_sales.as_slice() {
+            [sale] => (
+                format!("{} made a sale", sale.merchant),
+                format!("{} for {}", sale.item, format_price(sale.price_iron)),
+            ),
+            sales => (
+                format!("{} merchant sales", sales.len()),
+                format!(
+                    "{} in total",
+                    format_price(sales.iter().map(|sale| sale.price_iron).sum())
+                ),
+            ),
+        };
+        if let Err(err) = app_handle
+            .notification()
+            .builder()
+            .title(title)
+            .body(body)
+            .show()
+        {
+            warn!("Failed to show merchant sale notification: {:?}", err);
+        }
+
+        if let Ok(mut sales) = store.lock() {
+            sales.extend(new_sales);
+            if let Err(err) = persist_merchant_sales(&sales) {
+                warn!("Failed to persist merchant sales: {}", err);
+            }
+            let snapshot = sales.clone();
+            drop(sales);
+
+            if let Err(err) = app_handle.emit("merchant-sales", snapshot) {
+                warn!("Failed to emit merchant sales: {:?}", err);
+            }
+        }
+    }
+
+    fn flush(&mut self, price_history: &SharedPriceHistory, force: bool) {
+        let Some(since) = self.price_history_since else {
+            return;
+        };
+        if !force && since.elapsed() < PRICE_HISTORY_SAVE_DELAY {
+            return;
+        }
+        self.price_history_since = None;
+        if let Ok(history) = price_history.lock() {
+            if let Err(err) = persist_price_history(&history) {
+                warn!("Failed to persist price history: {}", err);
+            }
+        }
+    }
+}
 
 pub struct DirectoryWatcher {
     app_handle: AppHandle,
     skill_sessions: SharedSkillSessions,
     settings: SharedSettings,
     trade_entries: SharedTradeEntries,
+    price_history: SharedPriceHistory,
+    merchant_sales: SharedMerchantSales,
     granger_entries: SharedGrangerEntries,
     poll_interval: Duration,
 }
@@ -27,6 +119,8 @@ impl DirectoryWatcher {
         settings: SharedSettings,
         skill_sessions: SharedSkillSessions,
         trade_entries: SharedTradeEntries,
+        price_history: SharedPriceHistory,
+        merchant_sales: SharedMerchantSales,
         granger_entries: SharedGrangerEntries,
     ) -> Self {
         Self {
@@ -34,6 +128,8 @@ impl DirectoryWatcher {
             skill_sessions,
             settings,
             trade_entries,
+            price_history,
+            merchant_sales,
             granger_entries,
             poll_interval: Duration::from_millis(500),
         }
@@ -49,20 +145,26 @@ impl DirectoryWatcher {
             skill_sessions,
             settings,
             trade_entries,
+            price_history,
+            merchant_sales,
             granger_entries,
             poll_interval,
         } = self;
 
         let mut file_line_counts: HashMap<String, usize> = HashMap::new();
+        let mut log_clocks: HashMap<String, LogClock> = HashMap::new();
         let mut active_watch_dir = String::new();
         let mut logged_read_failures: HashSet<String> = HashSet::new();
         let mut granger_sessions: HashMap<String, PendingGrangerSession> = HashMap::new();
+        let mut pending_writes = PendingWrites::default();
+        // Only the first of a run of identical directory errors is printed.
+        let mut last_dir_error: Option<String> = None;
 
         loop {
             let current_watch_dir = match settings.lock() {
                 Ok(guard) => guard.watch_dir.clone(),
                 Err(err) => {
-                    println!(
+                    warn!(
                         "Failed to lock settings ({}); reusing previous watch directory",
                         err
                     );
@@ -72,9 +174,10 @@ impl DirectoryWatcher {
 
             if current_watch_dir.trim().is_empty() {
                 if !active_watch_dir.is_empty() {
-                    println!("Watch directory cleared; resetting state");
+                    info!("Watch directory cleared; resetting state");
                     active_watch_dir.clear();
                     file_line_counts.clear();
+                    log_clocks.clear();
                     logged_read_failures.clear();
                     granger_sessions.clear();
                     if let Ok(mut sessions) = skill_sessions.lock() {
@@ -83,10 +186,10 @@ impl DirectoryWatcher {
                     if let Err(err) =
                         app_handle.emit("skill-sessions", Vec::<SkillSessionData>::new())
                     {
-                        println!("Failed to emit skill session reset: {:?}", err);
+                        warn!("Failed to emit skill session reset: {:?}", err);
                     }
                     if let Err(err) = app_handle.emit("trade-entries", Vec::<TradeEntry>::new()) {
-                        println!("Failed to emit trade entry reset: {:?}", err);
+                        warn!("Failed to emit trade entry reset: {:?}", err);
                     }
                     // Preserve stored Granger data when watch directory is cleared
                 }
@@ -95,9 +198,11 @@ impl DirectoryWatcher {
             }
 
             if active_watch_dir != current_watch_dir {
-                println!("Switching watch directory to {}", current_watch_dir);
+                info!("Switching watch directory to {}", current_watch_dir);
+                pending_writes.flush(&price_history, true);
                 active_watch_dir = current_watch_dir.clone();
                 file_line_counts.clear();
+                log_clocks.clear();
                 logged_read_failures.clear();
 
                 if let Err(err) = Self::prime_directory_cache(
@@ -105,10 +210,7 @@ impl DirectoryWatcher {
                     &mut file_line_counts,
                     &mut logged_read_failures,
                 ) {
-                    println!(
-                        "Failed to read watch directory {}: {}",
-                        active_watch_dir, err
-                    );
+                    Self::report_dir_error(&active_watch_dir, err, &mut last_dir_error);
                     thread::sleep(Duration::from_millis(1000));
                     continue;
                 }
@@ -118,13 +220,13 @@ impl DirectoryWatcher {
                 }
                 if let Err(err) = app_handle.emit("skill-sessions", Vec::<SkillSessionData>::new())
                 {
-                    println!("Failed to emit skill session reset: {:?}", err);
+                    warn!("Failed to emit skill session reset: {:?}", err);
                 }
                 if let Ok(mut trades) = trade_entries.lock() {
                     trades.clear();
                 }
                 if let Err(err) = app_handle.emit("trade-entries", Vec::<TradeEntry>::new()) {
-                    println!("Failed to emit trade entry reset: {:?}", err);
+                    warn!("Failed to emit trade entry reset: {:?}", err);
                 }
                 // Preserve stored Granger data when switching watch directories
                 granger_sessions.clear();
@@ -137,26 +239,44 @@ impl DirectoryWatcher {
 
             if let Err(err) = Self::scan_directory(
                 &app_handle,
+                &settings,
                 &skill_sessions,
                 &trade_entries,
+                &price_history,
                 &granger_entries,
                 &active_watch_dir,
                 &mut file_line_counts,
+                &mut log_clocks,
                 &mut logged_read_failures,
                 &mut granger_sessions,
+                &mut pending_writes,
             ) {
-                println!(
-                    "Failed to read watch directory {}: {}",
-                    active_watch_dir, err
-                );
+                Self::report_dir_error(&active_watch_dir, err, &mut last_dir_error);
                 thread::sleep(Duration::from_millis(1000));
                 continue;
             }
+            last_dir_error = None;
+            pending_writes.flush_merchant_sales(&app_handle, &merchant_sales);
+            pending_writes.flush(&price_history, false);
+
+            Self::finalize_idle_granger_sessions(
+                &app_handle,
+                &settings,
+                &granger_entries,
+                &mut granger_sessions,
+            );
 
             thread::sleep(poll_interval);
         }
     }
 
+    fn report_dir_error(watch_dir: &str, err: String, last_dir_error: &mut Option<String>) {
+        if last_dir_error.as_deref() != Some(err.as_str()) {
+            warn!("Failed to read watch directory {}: {}", watch_dir, err);
+            *last_dir_error = Some(err);
+        }
+    }
+
     fn prime_directory_cache(
         watch_dir: &str,
         file_line_counts: &mut HashMap<String, usize>,
@@ -176,26 +296,31 @@ impl DirectoryWatcher {
                     }
                     Err(err) => {
                         if logged_read_failures.insert(path_str.clone()) {
-                            println!("Failed to read file {:?}: {}", path, err);
+                            warn!("Failed to read file {:?}: {}", path, err);
                         }
                     }
                 }
             }
         }
 
-        println!("Initial scan complete for {}", watch_dir);
+        info!("Initial scan complete for {}", watch_dir);
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn scan_directory(
         app_handle: &AppHandle,
+        settings: &SharedSettings,
         skill_sessions: &SharedSkillSessions,
         trade_entries: &SharedTradeEntries,
+        price_history: &SharedPriceHistory,
         granger_entries: &SharedGrangerEntries,
         watch_dir: &str,
         file_line_counts: &mut HashMap<String, usize>,
+        log_clocks: &mut HashMap<String, LogClock>,
         logged_read_failures: &mut HashSet<String>,
         granger_sessions: &mut HashMap<String, PendingGrangerSession>,
+        pending_writes: &mut PendingWrites,
     ) -> Result<(), String> {
         let entries = fs::read_dir(watch_dir).map_err(|err| err.to_string())?;
 
@@ -213,7 +338,7 @@ impl DirectoryWatcher {
                 }
                 Err(err) => {
                     if logged_read_failures.insert(path_str.clone()) {
-                        println!("Failed to read file {:?}: {}", path, err);
+                        warn!("Failed to read file {:?}: {}", path, err);
                     }
                     continue;
                 }
@@ -232,21 +357,35 @@ impl DirectoryWatcher {
             };
 
             if new_lines_start < total_lines {
+                // Catch up on the lines already seen to learn the file's date.
+                let clock = log_clocks.entry(path_str.clone()).or_insert_with(|| {
+                    let mut clock = LogClock::default();
+                    for line in &lines[..new_lines_start] {
+                        clock.observe(line.trim());
+                    }
+                    clock
+                });
+
                 for line in &lines[new_lines_start..] {
                     let trimmed_line = line.trim();
                     if trimmed_line.is_empty() {
                         continue;
                     }
+                    let logged_at = clock.observe(trimmed_line).unwrap_or_else(unix_now);
 
                     Self::handle_file_change(
                         app_handle,
+                        settings,
                         skill_sessions,
                         trade_entries,
+                        price_history,
                         granger_entries,
                         granger_sessions,
+                        pending_writes,
                         &path_str,
                         &chat_type,
                         trimmed_line,
+                        logged_at,
                     );
                 }
             }
@@ -257,22 +396,40 @@ impl DirectoryWatcher {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn handle_file_change(
         app_handle: &AppHandle,
+        settings: &SharedSettings,
         skill_sessions: &SharedSkillSessions,
         trade_entries: &SharedTradeEntries,
+        price_history: &SharedPriceHistory,
         granger_entries: &SharedGrangerEntries,
         granger_sessions: &mut HashMap<String, PendingGrangerSession>,
+        pending_writes: &mut PendingWrites,
         path: &str,
         chat_type: &str,
         last_line: &str,
+        logged_at: i64,
     ) {
-        println!("--- FILE CHANGED --- {}: {}", chat_type, last_line);
+        debug!("--- FILE CHANGED --- {}: {}", chat_type, last_line);
 
         Self::handle_skill_gain(app_handle, skill_sessions, last_line);
-        Self::handle_trade_message(app_handle, trade_entries, last_line);
+        // Only trade channels feed the monitor; Event, Local and PM logs do not.
+        if chat_type == "Trade" {
+            Self::handle_trade_message(
+                app_handle,
+                trade_entries,
+                price_history,
+                pending_writes,
+                path,
+                last_line,
+                logged_at,
+            );
+        }
+        Self::handle_merchant_sale(pending_writes, chat_type, last_line, logged_at);
         Self::handle_granger_message(
             app_handle,
+            settings,
             granger_entries,
             granger_sessions,
             path,
@@ -304,7 +461,7 @@ impl DirectoryWatcher {
                 entry.session_gain = current_level - entry.start_level;
                 entry.last_gain = gain;
 
-                println!(
+                debug!(
                     "--- SKILL GAIN --- {}: +{:.4} (session: +{:.4})",
                     skill_name, gain, entry.session_gain
                 );
@@ -313,11 +470,11 @@ impl DirectoryWatcher {
                 drop(sessions);
 
                 if let Err(err) = app_handle.emit("skill-sessions", session_data_vec.clone()) {
-                    println!("Failed to emit skill sessions to main window: {:?}", err);
+                    warn!("Failed to emit skill sessions to main window: {:?}", err);
                 }
                 if let Err(err) = app_handle.emit_to("skills", "skill-sessions", session_data_vec) {
                     if !matches!(err, tauri::Error::WebviewNotFound) {
-                        println!("Failed to emit skill sessions to skills window: {:?}", err);
+                        warn!("Failed to emit skill sessions to skills window: {:?}", err);
                     }
                 }
             }
@@ -332,31 +489,74 @@ impl DirectoryWatcher {
         };
 
         if let Err(err) = app_handle.emit("file-changed", payload) {
-            println!("Failed to emit file change event: {:?}", err);
+            warn!("Failed to emit file change event: {:?}", err);
         }
     }
 
     fn handle_trade_message(
         app_handle: &AppHandle,
         trade_entries: &SharedTradeEntries,
+        price_history: &SharedPriceHistory,
+        pending_writes: &mut PendingWrites,
+        path: &str,
         line: &str,
+        logged_at: i64,
     ) {
-        if let Some(entry) = parse_trade_entry(line) {
+        if let Some(entry) = parse_trade_entry(line, &log_channel_name(path), logged_at) {
+            let samples = price_samples_for(&entry);
+
             if let Ok(mut entries) = trade_entries.lock() {
-                entries.push(entry.clone());
-                truncate_entries(&mut entries, 200);
+                let folded = record_entry(&mut entries, entry);
+                truncate_entries(&mut entries, MAX_ENTRIES_PER_CHANNEL);
                 let snapshot = entries.clone();
                 drop(entries);
 
                 if let Err(err) = app_handle.emit("trade-entries", snapshot) {
-                    println!("Failed to emit trade entries: {:?}", err);
+                    warn!("Failed to emit trade entries: {:?}", err);
+                }
+
+                // Reposts were already sampled when first seen.
+                if !folded && !samples.is_empty() {
+                    if let Ok(mut history) = price_history.lock() {
+                        record_samples(&mut history, samples, unix_now());
+                        pending_writes
+                            .price_history_since
+                            .get_or_insert_with(Instant::now);
+                    }
                 }
             }
         }
     }
 
+    fn handle_merchant_sale(
+        pending_writes: &mut PendingWrites,
+        chat_type: &str,
+        line: &str,
+        logged_at: i64,
+    ) {
+        if !chat_type.eq_ignore_ascii_case("event") {
+            return;
+        }
+
+        let Some(sale) = parse_merchant_sale(line, logged_at) else {
+            if line.contains(" sold ") {
+                debug!("Event line mentions a sale but did not parse: {}", line);
+            }
+            return;
+        };
+
+        info!(
+            "--- MERCHANT SALE --- {} sold {} for {}",
+            sale.merchant,
+            sale.item,
+            format_price(sale.price_iron)
+        );
+        pending_writes.merchant_sales.push(sale);
+    }
+
     fn handle_granger_message(
         app_handle: &AppHandle,
+        settings: &SharedSettings,
         granger_entries: &SharedGrangerEntries,
         granger_sessions: &mut HashMap<String, PendingGrangerSession>,
         path: &str,
@@ -367,49 +567,91 @@ impl DirectoryWatcher {
             return;
         }
 
+        // A line logged well after the examine belongs to something else.
+        let line_seconds = extract_timestamp(line).and_then(|value| seconds_of_day(&value));
+        if granger_sessions
+            .get(path)
+            .is_some_and(|active| active.is_quiet_at(line_seconds))
+        {
+            if let Some(session) = granger_sessions.remove(path) {
+                Self::finalize_granger_session(app_handle, settings, granger_entries, session);
+            }
+        }
+
         if let Some(session) = PendingGrangerSession::from_smile_line(line) {
             if let Some(previous) = granger_sessions.remove(path) {
-                Self::finalize_granger_session(app_handle, granger_entries, previous);
+                Self::finalize_granger_session(app_handle, settings, granger_entries, previous);
             }
             granger_sessions.insert(path.to_string(), session);
             return;
         }
 
         if let Some(active) = granger_sessions.get_mut(path) {
-            active.absorb_line(line);
-            if active.is_ready() {
+            if active.absorb_line(line) == LineVerdict::Rejected {
+                debug!("Ignoring event line during granger examine: {}", line);
+            }
+            if active.state == GrangerSessionState::Complete {
                 if let Some(session) = granger_sessions.remove(path) {
-                    Self::finalize_granger_session(app_handle, granger_entries, session);
+                    Self::finalize_granger_session(app_handle, settings, granger_entries, session);
                 }
             }
         }
     }
 
+    /// Finalises examines that have had no new lines for `GRANGER_QUIET_GAP`,
+    /// so animals without a closing line are still recorded.
+    fn finalize_idle_granger_sessions(
+        app_handle: &AppHandle,
+        settings: &SharedSettings,
+        granger_entries: &SharedGrangerEntries,
+        granger_sessions: &mut HashMap<String, PendingGrangerSession>,
+    ) {
+        let idle: Vec<String> = granger_sessions
+            .iter()
+            .filter(|(_, session)| session.last_activity.elapsed() >= GRANGER_QUIET_GAP)
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        for path in idle {
+            if let Some(session) = granger_sessions.remove(&path) {
+                Self::finalize_granger_session(app_handle, settings, granger_entries, session);
+            }
+        }
+    }
+
     fn finalize_granger_session(
         app_handle: &AppHandle,
+        settings: &SharedSettings,
         granger_entries: &SharedGrangerEntries,
         session: PendingGrangerSession,
     ) {
-        if let Some(animal) = session.into_animal() {
-            if let Ok(mut entries) = granger_entries.lock() {
-                entries.insert(animal.id.clone(), animal.clone());
-                if let Err(err) = persist_granger(&entries) {
-                    println!("Failed to persist granger data: {}", err);
+        if let Some(mut animal) = session.into_animal() {
+            let weights = match settings.lock() {
+                Ok(guard) => guard.trait_weights.clone(),
+                Err(err) => {
+                    warn!("Failed to lock settings for trait weights ({})", err);
+                    TraitWeights::new()
                 }
-                let snapshot = granger_to_vec(&entries);
-                drop(entries);
+            };
+            animal.breeding_score = breeding_score(&animal.canonical_traits, &weights);
 
-                if let Err(err) = app_handle.emit("granger-entries", snapshot.clone()) {
-                    println!("Failed to emit granger entries: {:?}", err);
-                }
-                if let Err(err) = app_handle.emit_to("granger", "granger-entries", snapshot) {
-                    if !matches!(err, tauri::Error::WebviewNotFound) {
-                        println!(
-                            "Failed to emit granger entries to granger window: {:?}",
-                            err
+            if let Ok(mut entries) = granger_entries.lock() {
+                let id = record_examine(&mut entries, animal);
+                if let Some(stored) = entries.get(&id) {
+                    if !stored.ambiguous_with.is_empty() {
+                        info!(
+                            "Granger examine of {} matches several animals: {:?}",
+                            stored.name, stored.ambiguous_with
                         );
                     }
                 }
+                if let Err(err) = persist_granger_animals(&entries, &[id]) {
+                    warn!("Failed to persist granger data: {}", err);
+                }
+                let snapshot = granger_to_vec(&entries);
+                drop(entries);
+
+                broadcast_granger(app_handle, snapshot);
             }
         }
     }
@@ -422,26 +664,65 @@ struct FileChangeEvent {
     chat_type: String,
 }
 
+// Examine output is printed in one burst; a longer silence ends the session.
+const GRANGER_QUIET_GAP: Duration = Duration::from_secs(5);
+
+/// Species whose examine ends with a colour line, keyed by the first word of
+/// the "... like this one" line. Other species finalise after the quiet gap.
+const COLOURED_SPECIES: &[&str] = &["horse", "horses", "foal", "foals", "hell"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GrangerSessionState {
+    /// Smile seen, waiting for the species line.
+    Started,
+    /// Collecting condition, traits and other examine details.
+    Describing,
+    /// The species' closing line arrived.
+    Complete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineVerdict {
+    Absorbed,
+    Rejected,
+}
+
 #[derive(Debug, Clone)]
 struct PendingGrangerSession {
+    state: GrangerSessionState,
+    last_line_seconds: Option<u32>,
+    last_activity: Instant,
     timestamp: String,
     name: Option<String>,
     descriptors: Vec<String>,
     age: Option<String>,
     custom_label: Option<String>,
     species: Option<String>,
+    sex: Option<String>,
     settlement: Option<String>,
     caretaker: Option<String>,
     condition: Option<String>,
     traits: Vec<String>,
+    canonical_traits: Vec<CreatureTrait>,
+    unrecognised_traits: Vec<String>,
     trait_points: Option<u32>,
     colour: Option<String>,
+    pregnant: bool,
+    birth_due: Option<String>,
+    due_in_days: Option<f64>,
+    branded: bool,
+    brand_owner: Option<String>,
+    mother: Option<String>,
+    father: Option<String>,
+    genesis: Option<String>,
+    status_lines: Vec<String>,
     raw_lines: Vec<String>,
+    unparsed_lines: Vec<String>,
 }
 
 impl PendingGrangerSession {
     fn from_smile_line(line: &str) -> Option<Self> {
-        let timestamp = extract_timestamp(line).unwrap_or_else(|| "".to_string());
+        let timestamp = extract_timestamp(line).unwrap_or_default();
         let content = strip_timestamp_prefix(line);
 
         if !content.starts_with("You smile at") {
@@ -481,27 +762,71 @@ impl PendingGrangerSession {
         };
 
         Some(Self {
+            state: GrangerSessionState::Started,
+            last_line_seconds: seconds_of_day(&timestamp),
+            last_activity: Instant::now(),
             timestamp,
             name,
             descriptors,
             age,
             custom_label,
             species: None,
+            sex: None,
             settlement: None,
             caretaker: None,
             condition: None,
             traits: Vec::new(),
+            canonical_traits: Vec::new(),
+            unrecognised_traits: Vec::new(),
             trait_points: None,
             colour: None,
+            pregnant: false,
+            birth_due: None,
+            due_in_days: None,
+            branded: false,
+            brand_owner: None,
+            mother: None,
+            father: None,
+            genesis: None,
+            status_lines: Vec::new(),
             raw_lines: vec![content.to_string()],
+            unparsed_lines: Vec::new(),
         })
     }
 
-    fn absorb_line(&mut self, line: &str) {
+    /// Whether a line logged at `line_seconds` comes after the session has
+    /// gone quiet. Lines without a timestamp fall back to wall-clock time.
+    fn is_quiet_at(&self, line_seconds: Option<u32>) -> bool {
+        match (self.last_line_seconds, line_seconds) {
+            (Some(last), Some(current)) => {
+                // Logs only carry the time of day, so allow for midnight.
+                let gap = (current + 86_400 - last) % 86_400;
+                u64::from(gap) >= GRANGER_QUIET_GAP.as_secs()
+            }
+            _ => self.last_activity.elapsed() >= GRANGER_QUIET_GAP,
+        }
+    }
+
+    fn absorb_line(&mut self, line: &str) -> LineVerdict {
         let content = strip_timestamp_prefix(line);
+        if !is_examine_line(content) {
+            return LineVerdict::Rejected;
+        }
+
         self.raw_lines.push(content.to_string());
+        self.last_activity = Instant::now();
+        if let Some(seconds) = extract_timestamp(line).and_then(|value| seconds_of_day(&value)) {
+            self.last_line_seconds = Some(seconds);
+        }
+        if self.state == GrangerSessionState::Started {
+            self.state = GrangerSessionState::Describing;
+        }
+
+        let lowered = content.to_ascii_lowercase();
+        let mut recognised = false;
 
         if self.species.is_none() && content.contains(" like this one") {
+            recognised = true;
             if let Some(first_word) = content.split_whitespace().next() {
                 let species = first_word.trim_matches(|c: char| c == '.' || c == ',');
                 if !species.is_empty() {
@@ -510,16 +835,30 @@ impl PendingGrangerSession {
             }
         }
 
-        if self.settlement.is_none() && content.contains("settlement of ") {
+        if self.sex.is_none() {
+            self.sex = sex_from_sentence(content);
+        }
+
+        if content.contains("settlement of ") {
+            recognised = true;
             if let Some(after) = content.split("settlement of ").nth(1) {
                 let settlement = after.trim().trim_end_matches('.');
-                if !settlement.is_empty() {
+                if !settlement.is_empty() && self.settlement.is_none() {
                     self.settlement = Some(settlement.to_string());
                 }
             }
         }
 
+        if lowered.contains("branded") {
+            recognised = true;
+            self.branded = true;
+            if self.brand_owner.is_none() {
+                self.brand_owner = text_after(content, &["settlement of ", "branded by "]);
+            }
+        }
+
         if self.caretaker.is_none() && content.contains("taken care of by") {
+            recognised = true;
             if let Some(after) = content.split("by ").nth(1) {
                 let caretaker = after.trim().trim_end_matches('.');
                 if !caretaker.is_empty() {
@@ -528,70 +867,151 @@ impl PendingGrangerSession {
             }
         }
 
-        if self.condition.is_none()
-            && (content.starts_with("He is")
-                || content.starts_with("She is")
-                || content.starts_with("It is"))
-        {
-            if !content.contains("trait points") && !content.contains("colour is") {
+        let pregnancy_line = lowered.contains("pregnant") || lowered.contains("give birth");
+        if pregnancy_line {
+            recognised = true;
+            self.pregnant = true;
+            if let Some(due) = text_after(content, &["give birth in ", "deliver in "]) {
+                self.due_in_days = parse_due_in_days(&due);
+                self.birth_due = Some(due);
+            }
+        }
+
+        if lowered.contains("mother is ") || lowered.contains("mother was ") {
+            recognised = true;
+            self.mother = text_after(content, &["mother is ", "mother was "]);
+        }
+
+        if lowered.contains("father is ") || lowered.contains("father was ") {
+            recognised = true;
+            self.father = text_after(content, &["father is ", "father was "]);
+        }
+
+        if lowered.contains("genesis") {
+            recognised = true;
+            self.genesis = Some(content.trim().to_string());
+        }
+
+        let is_status_line = (content.starts_with("He is")
+            || content.starts_with("She is")
+            || content.starts_with("It is"))
+            && !content.contains("trait points")
+            && !content.contains("colour is")
+            && !pregnancy_line;
+
+        if is_status_line {
+            recognised = true;
+            if self.condition.is_none() {
                 self.condition = Some(content.to_string());
+            } else if !self.status_lines.iter().any(|existing| existing == content) {
+                self.status_lines.push(content.to_string());
             }
         }
 
         if content.contains("trait points") {
+            recognised = true;
             if let Some(value) = extract_number(content) {
                 self.trait_points = Some(value);
             }
         } else {
-            for trait_text in split_trait_sentences(content) {
-                if self
-                    .traits
-                    .iter()
-                    .any(|existing| existing.eq_ignore_ascii_case(&trait_text))
-                {
-                    continue;
+            for sentence in content
+                .split('.')
+                .map(str::trim)
+                .filter(|part| !part.is_empty())
+            {
+                if let Some(creature_trait) = classify_sentence(sentence) {
+                    recognised = true;
+                    if !self.canonical_traits.contains(&creature_trait) {
+                        self.canonical_traits.push(creature_trait);
+                        self.traits.push(trait_label(creature_trait).to_string());
+                    }
+                } else if is_trait_sentence(sentence) {
+                    recognised = true;
+                    if !self
+                        .unrecognised_traits
+                        .iter()
+                        .any(|existing| existing.eq_ignore_ascii_case(sentence))
+                    {
+                        info!("Unrecognised granger trait: {}", sentence);
+                        self.unrecognised_traits.push(sentence.to_string());
+                    }
                 }
-                self.traits.push(trait_text);
             }
         }
 
-        if self.colour.is_none() && content.contains("colour is") {
+        if content.contains("colour is") {
+            recognised = true;
             if let Some(after) = content.split("colour is").nth(1) {
                 let colour = after.trim().trim_end_matches('.');
-                if !colour.is_empty() {
+                if !colour.is_empty() && self.colour.is_none() {
                     self.colour = Some(colour.to_string());
                 }
             }
+            if self.expects_colour() {
+                self.state = GrangerSessionState::Complete;
+            }
         }
+
+        if !recognised {
+            self.unparsed_lines.push(content.to_string());
+        }
+        LineVerdict::Absorbed
     }
 
-    fn is_ready(&self) -> bool {
-        self.colour.is_some()
+    fn expects_colour(&self) -> bool {
+        self.species.as_deref().is_none_or(|species| {
+            COLOURED_SPECIES
+                .iter()
+                .any(|known| species.eq_ignore_ascii_case(known))
+        })
     }
 
     fn into_animal(self) -> Option<GrangerAnimal> {
         let name = self.name?;
-        let id = name.clone();
 
+        // The store assigns the id once it knows which animal this is.
         Some(GrangerAnimal {
-            id,
+            id: String::new(),
             name,
             descriptors: self.descriptors,
             age: self.age,
             custom_label: self.custom_label,
             species: self.species,
+            sex: self.sex,
             settlement: self.settlement,
             caretaker: self.caretaker,
             condition: self.condition,
             traits: self.traits,
+            canonical_traits: self.canonical_traits,
+            unrecognised_traits: self.unrecognised_traits,
+            breeding_score: 0.0,
             trait_points: self.trait_points,
             colour: self.colour,
+            pregnant: self.pregnant,
+            birth_due: self.birth_due,
+            due_in_days: self.due_in_days,
+            expected_birth_at: None,
+            birth_notified: false,
+            branded: self.branded,
+            brand_owner: self.brand_owner,
+            mother: self.mother,
+            father: self.father,
+            genesis: self.genesis,
+            status_lines: self.status_lines,
+            raw_lines: self.unparsed_lines,
             updated_at: self.timestamp,
+            ambiguous_with: Vec::new(),
+            distinct_from: Vec::new(),
+            examinations: Vec::new(),
+            archived: false,
+            tags: Vec::new(),
+            notes: None,
+            overridden_fields: Vec::new(),
         })
     }
 }
 
-fn get_chat_type(path_str: &str) -> String {
+pub(crate) fn get_chat_type(path_str: &str) -> String {
     if let Some(filename) = Path::new(path_str).file_name() {
         if let Some(filename_str) = filename.to_str() {
             if filename_str.starts_with('_') {
@@ -622,6 +1042,19 @@ fn get_chat_type(path_str: &str) -> String {
     "unknown".to_string()
 }
 
+/// Returns the channel a log file belongs to, taken from its file name, so
+/// `Trade-Xanadu.2025-10.txt` becomes `Trade-Xanadu`.
+pub(crate) fn log_channel_name(path_str: &str) -> String {
+    Path::new(path_str)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.split('.').next())
+        .map(|stem| stem.trim_start_matches('_'))
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
 fn strip_timestamp_prefix(line: &str) -> &str {
     if line.starts_with('[') {
         if let Some(index) = line.find("] ") {
@@ -640,6 +1073,121 @@ fn extract_timestamp(line: &str) -> Option<String> {
     None
 }
 
+/// Reads a log "HH:MM:SS" timestamp as seconds since midnight.
+fn seconds_of_day(timestamp: &str) -> Option<u32> {
+    let mut parts = timestamp.split(':').map(|part| part.parse::<u32>().ok());
+    let hours = parts.next()??;
+    let minutes = parts.next()??;
+    let seconds = parts.next().unwrap_or(Some(0))?;
+    Some(hours * 3600 + minutes * 60 + seconds)
+}
+
+// A jump back in time bigger than this between lines means midnight passed.
+const MIDNIGHT_ROLLOVER_SECS: u32 = 12 * 60 * 60;
+
+/// Dates log lines. A line carries only "[HH:MM:SS]"; the day comes from the
+/// file's latest "Logging started" line and moves on when the clock wraps
+/// past midnight.
+#[derive(Clone, Default)]
+pub(crate) struct LogClock {
+    date: Option<NaiveDate>,
+    last_seconds: Option<u32>,
+}
+
+impl LogClock {
+    /// Reads the next line of a file and returns when it was logged as a Unix
+    /// timestamp, if the line and the file's date allow it.
+    pub(crate) fn observe(&mut self, line: &str) -> Option<i64> {
+        if let Some(date) = line.strip_prefix("Logging started ") {
+            self.date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok();
+            self.last_seconds = None;
+            return None;
+        }
+
+        let seconds = seconds_of_day(&extract_timestamp(line)?)?;
+        let date = self.date.as_mut()?;
+        if self
+            .last_seconds
+            .is_some_and(|last| last > seconds + MIDNIGHT_ROLLOVER_SECS)
+        {
+            *date = date.succ_opt()?;
+        }
+        self.last_seconds = Some(seconds);
+
+        let time = NaiveTime::from_num_seconds_from_midnight_opt(seconds, 0)?;
+        Local
+            .from_local_datetime(&date.and_time(time))
+            .earliest()
+            .map(|logged_at| logged_at.timestamp())
+    }
+}
+
+/// Whether an event line reads like part of a creature examine. Anything else
+/// logged mid-examine (skill ticks, combat, trade) is left out of the record.
+/// Trait sentences count whatever their subject, e.g. "The legs are of
+/// different length."
+fn is_examine_line(content: &str) -> bool {
+    const SUBJECTS: &[&str] = &["It ", "Its ", "He ", "His ", "She ", "Her "];
+    if SUBJECTS.iter().any(|subject| content.starts_with(subject)) {
+        return true;
+    }
+    if content
+        .split('.')
+        .map(str::trim)
+        .any(|sentence| !sentence.is_empty() && classify_sentence(sentence).is_some())
+    {
+        return true;
+    }
+
+    let lowered = content.to_ascii_lowercase();
+    [
+        " like this one",
+        "taken care of by",
+        "settlement of ",
+        "trait points",
+        "colour is",
+        "branded",
+        "pregnant",
+        "genesis",
+    ]
+    .iter()
+    .any(|marker| lowered.contains(marker))
+}
+
+/// Returns the text following the first of `markers` found in `content`, up
+/// to the end of that sentence.
+fn text_after(content: &str, markers: &[&str]) -> Option<String> {
+    let lowered = content.to_ascii_lowercase();
+    markers.iter().find_map(|marker| {
+        let start = lowered.find(&marker.to_ascii_lowercase())? + marker.len();
+        let value = content[start..].split('.').next()?.trim();
+        if value.is_empty() {
+            None
+        } else {
+            Some(value.to_string())
+        }
+    })
+}
+
+/// Reads "about 3 days" or "12 hours" style durations as days.
+fn parse_due_in_days(text: &str) -> Option<f64> {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric() && c != '.'))
+        .map(|word| word.to_ascii_lowercase())
+        .collect();
+
+    words.windows(2).find_map(|pair| {
+        let amount = pair[0].parse::<f64>().ok()?;
+        match pair[1].trim_end_matches('s') {
+            "day" => Some(amount),
+            "hour" => Some(amount / 24.0),
+            "week" => Some(amount * 7.0),
+            _ => None,
+        }
+    })
+}
+
 fn extract_number(line: &str) -> Option<u32> {
     let digits: String = line.chars().filter(|c| c.is_ascii_digit()).collect();
     if digits.is_empty() {
@@ -649,31 +1197,24 @@ fn extract_number(line: &str) -> Option<u32> {
     }
 }
 
-fn split_trait_sentences(line: &str) -> Vec<String> {
-    line.split('.')
-        .map(|part| part.trim())
-        .filter(|part| !part.is_empty())
-        .filter_map(|sentence| {
-            if !(sentence.starts_with("It has")
-                || sentence.starts_with("He has")
-                || sentence.starts_with("She has"))
-            {
-                return None;
-            }
+/// Whether a sentence reads like a trait ("It has ...") rather than points,
+/// breeding history or another status line.
+fn is_trait_sentence(sentence: &str) -> bool {
+    if !(sentence.starts_with("It has")
+        || sentence.starts_with("He has")
+        || sentence.starts_with("She has"))
+    {
+        return false;
+    }
 
-            let lowered = sentence.to_ascii_lowercase();
-            if lowered.contains("trait points") || lowered.contains("has been") {
-                return None;
-            }
+    let lowered = sentence.to_ascii_lowercase();
+    if lowered.contains("trait points") || lowered.contains("has been") {
+        return false;
+    }
 
-            let fragment = sentence.splitn(2, " has ").nth(1)?.trim();
-            if fragment.is_empty() {
-                None
-            } else {
-                Some(fragment.to_string())
-            }
-        })
-        .collect()
+    sentence
+        .split_once(" has ")
+        .is_some_and(|(_, fragment)| !fragment.trim().is_empty())
 }
 
 fn extract_custom_label(input: &str) -> (Option<String>, String) {
@@ -766,12 +1307,13 @@ fn parse_skill_gain(line: &str) -> Option<(String, f64, f64)> {
     None
 }
 
-fn read_file_contents(path: &Path) -> Result<String, std::io::Error> {
+pub(crate) fn read_file_contents(path: &Path) -> Result<String, std::io::Error> {
     let bytes = fs::read(path)?;
     Ok(String::from_utf8_lossy(&bytes).into_owned())
 }
 
-fn parse_trade_entry(line: &str) -> Option<TradeEntry> {
+/// `logged_at` is when the line was written, from its log timestamp.
+pub(crate) fn parse_trade_entry(line: &str, channel: &str, logged_at: i64) -> Option<TradeEntry> {
     if line.trim().is_empty() {
         return None;
     }
@@ -791,14 +1333,62 @@ fn parse_trade_entry(line: &str) -> Option<TradeEntry> {
     }
 
     let category = classify_trade_message(&message)?;
+    let speaker = extract_speaker(&message);
+    let items = parse_trade_items(&message);
 
     Some(TradeEntry {
         category,
+        channel: channel.to_string(),
+        last_timestamp: timestamp.clone(),
         timestamp,
         message,
+        speaker,
+        items,
+        repost_count: 0,
+        first_seen: logged_at,
+        last_seen: logged_at,
     })
 }
 
+fn price_samples_for(entry: &TradeEntry) -> Vec<PriceSample> {
+    if entry.category != "WTS" && entry.category != "WTB" {
+        return Vec::new();
+    }
+
+    entry
+        .items
+        .iter()
+        .filter_map(|item| {
+            Some(PriceSample {
+                item: normalise_item(&item.name),
+                ql: item.ql,
+                price_iron: item.price_iron?,
+                category: entry.category.clone(),
+                speaker: entry.speaker.clone(),
+                seen_at: entry.first_seen,
+            })
+        })
+        .collect()
+}
+
+fn extract_speaker(message: &str) -> Option<String> {
+    let rest = message.strip_prefix('<')?;
+    let end = rest.find('>')?;
+    let speaker = rest[..end].trim();
+    if speaker.is_empty() {
+        None
+    } else {
+        Some(speaker.to_string())
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or(0)
+}
+
 fn classify_trade_message(message: &str) -> Option<String> {
     let trimmed = message.trim();
     if trimmed.is_empty() {
@@ -854,3 +1444,205 @@ fn classify_trade_message(message: &str) -> Option<String> {
 
     None
 }
+
+fn parse_trade_items(message: &str) -> Vec<TradeItem> {
+    let mut body = message.trim();
+
+    if body.starts_with('<') {
+        if let Some(end) = body.find('>') {
+            body = body[end + 1..].trim_start();
+        }
+    }
+
+    if body.starts_with('(') {
+        if let Some(end) = body.find(')') {
+            body = body[end + 1..].trim_start();
+        }
+    }
+
+    body.split([',', ';', '|', '\n'])
+        .filter_map(parse_trade_item_segment)
+        .collect()
+}
+
+fn parse_trade_item_segment(segment: &str) -> Option<TradeItem> {
+    let tokens: Vec<String> = segment
+        .split_whitespace()
+        .map(|token| {
+            token
+                .trim_matches(|c: char| !c.is_alphanumeric() && c != '.')
+                .trim_end_matches('.')
+                .to_ascii_lowercase()
+        })
+        .filter(|token| !token.is_empty())
+        .collect();
+
+    let mut name_words: Vec<&str> = Vec::new();
+    let mut ql = None;
+    let mut price_iron = None;
+    let mut index = 0;
+
+    while index < tokens.len() {
+        let token = tokens[index].as_str();
+        let next = tokens.get(index + 1).map(String::as_str);
+
+        if let Some(value) = parse_price(token) {
+            price_iron.get_or_insert(value);
+        } else if let (Ok(amount), Some(unit)) = (
+            token.parse::<f64>(),
+            next.filter(|unit| !matches!(*unit, "i" | "iron"))
+                .and_then(coin_value),
+        ) {
+            price_iron.get_or_insert((amount * unit as f64).round() as u64);
+            index += 1;
+        } else if token == "ql" {
+            if let Some(value) = next.and_then(|next| next.parse::<f64>().ok()) {
+                ql = Some(value);
+                index += 1;
+            }
+        } else if let Some(value) = token
+            .strip_prefix("ql")
+            .or_else(|| token.strip_suffix("ql"))
+            .and_then(|value| value.parse::<f64>().ok())
+        {
+            ql = Some(value);
+        } else if let (Ok(value), Some("ql")) = (token.parse::<f64>(), next) {
+            ql = Some(value);
+            index += 1;
+        } else if token.chars().all(|c| c.is_ascii_alphabetic())
+            && !TRADE_FILLER_WORDS.contains(&token)
+        {
+            name_words.push(token);
+        }
+
+        index += 1;
+    }
+
+    if name_words.is_empty() {
+        return None;
+    }
+
+    Some(TradeItem {
+        name: name_words.join(" "),
+        ql,
+        price_iron,
+    })
+}
+
+const TRADE_FILLER_WORDS: &[&str] = &[
+    "wts", "wtb", "wtt", "pc", "pm", "me", "for", "at", "each", "ea", "per", "price", "offers",
+    "offer", "or", "and", "a", "an", "the", "with", "x", "cod", "cheap", "selling", "buying",
+];
+
+// Wurm coins are gold, silver, copper and iron, each worth 100 of the next.
+fn coin_value(unit: &str) -> Option<u64> {
+    match unit {
+        "g" | "gold" => Some(1_000_000),
+        "s" | "silver" => Some(10_000),
+        "c" | "copper" => Some(100),
+        "i" | "iron" => Some(1),
+        _ => None,
+    }
+}
+
+/// Parses compact prices such as `5s`, `1.5s` or `2s50c` into iron.
+fn parse_price(token: &str) -> Option<u64> {
+    let mut total = 0.0;
+    let mut amount = String::new();
+    let mut saw_unit = false;
+
+    for ch in token.chars() {
+        if ch.is_ascii_digit() || ch == '.' {
+            amount.push(ch);
+        } else {
+            let value: f64 = amount.parse().ok()?;
+            total += value * coin_value(&ch.to_string())? as f64;
+            amount.clear();
+            saw_unit = true;
+        }
+    }
+
+    if !saw_unit || !amount.is_empty() {
+        return None;
+    }
+
+    Some(total.round() as u64)
+}
+
+/// Parses merchant sale messages from the Event log, for example
+/// `Merchant_Bob sold a pickaxe to Alice for 5 silver and 20 copper.`
+///
+/// Both accepted shapes, a `Merchant_` name or `Your merchant ...`, are
+/// inferred from the default merchant naming and have not been checked
+/// against a real `_Event` log. Event lines that mention a sale but do not
+/// match are logged at debug level so the real wording can be collected.
+fn parse_merchant_sale(line: &str, sold_at: i64) -> Option<MerchantSale> {
+    let timestamp = extract_timestamp(line).unwrap_or_default();
+    let content = strip_timestamp_prefix(line).trim().trim_end_matches('.');
+
+    let (merchant, rest) = content.split_once(" sold ")?;
+    let merchant = merchant
+        .trim()
+        .strip_prefix("Your merchant ")
+        .unwrap_or(merchant.trim());
+    if !merchant.starts_with("Merchant_") && !content.starts_with("Your merchant ") {
+        return None;
+    }
+
+    let (sold, price_text) = rest.rsplit_once(" for ")?;
+    let price_iron = parse_money_text(price_text)?;
+
+    let (item, buyer) = match sold.rsplit_once(" to ") {
+        Some((item, buyer)) => (item, Some(buyer.trim().to_string())),
+        None => (sold, None),
+    };
+    let item = item.trim();
+    let item = ["a ", "an ", "the "]
+        .iter()
+        .find_map(|article| item.strip_prefix(article))
+        .unwrap_or(item);
+    if item.is_empty() {
+        return None;
+    }
+
+    Some(MerchantSale {
+        merchant: merchant.to_string(),
+        item: item.to_string(),
+        buyer,
+        price_iron,
+        timestamp,
+        sold_at,
+    })
+}
+
+/// Parses spelled-out prices such as `1 silver, 20 copper and 5 iron`.
+fn parse_money_text(text: &str) -> Option<u64> {
+    let tokens: Vec<String> = text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .map(|token| token.trim_matches('.').to_ascii_lowercase())
+        .filter(|token| !token.is_empty() && token != "and")
+        .collect();
+
+    let mut total = 0;
+    let mut matched = false;
+    let mut index = 0;
+
+    while index < tokens.len() {
+        if let Some(value) = parse_price(&tokens[index]) {
+            total += value;
+            matched = true;
+        } else if let (Ok(amount), Some(unit)) = (
+            tokens[index].parse::<f64>(),
+            tokens.get(index + 1).and_then(|unit| coin_value(unit)),
+        ) {
+            total += (amount * unit as f64).round() as u64;
+            matched = true;
+            index += 1;
+        } else {
+            return None;
+        }
+        index += 1;
+    }
+
+    matched.then_some(total)
+}
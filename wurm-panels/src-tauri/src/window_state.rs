@@ -0,0 +1,218 @@
+use crate::app_settings::{persist_settings, SharedSettings, WindowPlacement, WindowState};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::webview::WebviewWindowBuilder;
+use tauri::{AppHandle, Emitter, LogicalPosition, LogicalSize, Manager, Runtime, WebviewWindow};
+use tracing::warn;
+
+pub const MIN_OPACITY: f64 = 0.2;
+
+// Moves and resizes arrive in bursts while dragging, so the settings file is
+// written once a panel has been still for this long.
+const GEOMETRY_SAVE_DELAY: Duration = Duration::from_millis(750);
+
+// When geometry last changed while a save is waiting.
+static PENDING_GEOMETRY: Mutex<Option<Instant>> = Mutex::new(None);
+
+// Panels whose state is remembered. The splash screen is left alone.
+pub const PANEL_LABELS: &[&str] = &[
+    "skills",
+    "farming-grind",
+    "settings",
+    "trade",
+    "granger",
+    "map",
+    "watcher",
+];
+
+/// Saved state for a panel, or the defaults if it has never been opened.
+pub fn saved(app: &AppHandle, label: &str) -> WindowState {
+    let settings = app.state::<SharedSettings>();
+    let state = match settings.lock() {
+        Ok(guard) => guard.windows.get(label).cloned(),
+        Err(err) => {
+            warn!(
+                "Failed to lock settings for {} window state: {}",
+                label, err
+            );
+            None
+        }
+    };
+    state.unwrap_or_default()
+}
+
+/// Restores the saved position, size and always-on-top flag on a window
+/// being built. Anything not saved keeps the builder's defaults.
+pub fn apply<'a, R: Runtime, M: Manager<R>>(
+    builder: WebviewWindowBuilder<'a, R, M>,
+    state: &WindowState,
+) -> WebviewWindowBuilder<'a, R, M> {
+    let mut builder = builder;
+    if let Some(placement) = &state.placement {
+        builder = builder
+            .position(placement.x, placement.y)
+            .inner_size(placement.width, placement.height);
+    }
+    if let Some(always_on_top) = state.always_on_top {
+        builder = builder.always_on_top(always_on_top);
+    }
+    builder
+}
+
+/// Marks a panel as open and hands it its saved state, e.g. for opacity.
+pub fn opened(app: &AppHandle, window: &WebviewWindow) {
+    let label = window.label().to_string();
+    update(app, &label, |state| state.visible = true);
+    if let Err(err) = window.emit_to(label.as_str(), "window-state", saved(app, &label)) {
+        warn!("Failed to send window state to {}: {:?}", label, err);
+    }
+}
+
+/// Remembers where a panel sits after it is moved or resized. The settings
+/// file is written after `GEOMETRY_SAVE_DELAY` without further changes.
+pub fn record_geometry<R: Runtime>(app: &AppHandle, window: &tauri::Window<R>) {
+    let scale = window.scale_factor().unwrap_or(1.0);
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        warn!("Failed to read {} window geometry", window.label());
+        return;
+    };
+    let position = position.to_logical::<f64>(scale);
+    let size = size.to_logical::<f64>(scale);
+
+    let label = window.label();
+    let settings = app.state::<SharedSettings>();
+    let Ok(mut guard) = settings.lock() else {
+        warn!("Failed to lock settings for {} window state", label);
+        return;
+    };
+    guard
+        .windows
+        .entry(label.to_string())
+        .or_default()
+        .placement = Some(WindowPlacement {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    });
+    drop(guard);
+
+    schedule_geometry_save(app);
+}
+
+fn schedule_geometry_save(app: &AppHandle) {
+    let Ok(mut pending) = PENDING_GEOMETRY.lock() else {
+        return;
+    };
+    let already_waiting = pending.is_some();
+    *pending = Some(Instant::now());
+    if already_waiting {
+        return;
+    }
+    drop(pending);
+
+    let app = app.clone();
+    thread::spawn(move || loop {
+        thread::sleep(GEOMETRY_SAVE_DELAY);
+        let Ok(mut pending) = PENDING_GEOMETRY.lock() else {
+            return;
+        };
+        if pending.is_some_and(|changed| changed.elapsed() < GEOMETRY_SAVE_DELAY) {
+            continue;
+        }
+        *pending = None;
+        drop(pending);
+
+        let settings = app.state::<SharedSettings>();
+        match settings.lock() {
+            Ok(guard) => {
+                if let Err(err) = persist_settings(&guard) {
+                    warn!("Failed to persist window geometry: {}", err);
+                }
+            }
+            Err(err) => warn!("Failed to lock settings to save window geometry: {}", err),
+        }
+        return;
+    });
+}
+
+/// Changes one panel's saved state and writes the settings file.
+pub fn update(app: &AppHandle, label: &str, change: impl FnOnce(&mut WindowState)) {
+    let settings = app.state::<SharedSettings>();
+    let Ok(mut guard) = settings.lock() else {
+        warn!("Failed to lock settings for {} window state", label);
+        return;
+    };
+    change(guard.windows.entry(label.to_string()).or_default());
+    if let Err(err) = persist_settings(&guard) {
+        warn!("Failed to persist {} window state: {}", label, err);
+    }
+}
+
+/// Brings panels in line with the saved layout after it changed underneath
+/// them, e.g. on a profile switch. Panels the layout has closed are closed
+/// and open ones are moved. The settings panel stays open, as switches are
+/// made from it.
+///
+/// Returns the panels the layout has open that are not, for the caller to
+/// open.
+pub fn reapply(app: &AppHandle) -> Vec<String> {
+    let mut to_open = Vec::new();
+    for label in PANEL_LABELS {
+        let visible = saved(app, label).visible;
+        match app.get_webview_window(label) {
+            Some(_) if *label == "settings" && !visible => {
+                update(app, label, |state| state.visible = true);
+            }
+            Some(window) if !visible => {
+                if let Err(err) = window.close() {
+                    warn!("Failed to close {} window: {:?}", label, err);
+                }
+            }
+            None if visible => to_open.push(label.to_string()),
+            _ => {}
+        }
+    }
+
+    for label in PANEL_LABELS {
+        let Some(window) = app.get_webview_window(label) else {
+            continue;
+        };
+        let state = saved(app, label);
+        if !state.visible {
+            continue;
+        }
+        if let Some(placement) = &state.placement {
+            let moved = window
+                .set_position(LogicalPosition::new(placement.x, placement.y))
+                .and_then(|_| window.set_size(LogicalSize::new(placement.width, placement.height)));
+            if let Err(err) = moved {
+                warn!("Failed to move {} window: {:?}", label, err);
+            }
+        }
+        if let Some(always_on_top) = state.always_on_top {
+            if let Err(err) = window.set_always_on_top(always_on_top) {
+                warn!("Failed to set always on top for {}: {:?}", label, err);
+            }
+        }
+        if let Err(err) = window.emit_to(*label, "window-state", state) {
+            warn!("Failed to send window state to {}: {:?}", label, err);
+        }
+    }
+    to_open
+}
+
+/// Labels of panels that were open when the app last ran.
+pub fn visible_labels(app: &AppHandle) -> Vec<String> {
+    let settings = app.state::<SharedSettings>();
+    let Ok(guard) = settings.lock() else {
+        return Vec::new();
+    };
+    guard
+        .windows
+        .iter()
+        .filter(|(label, state)| state.visible && PANEL_LABELS.contains(&label.as_str()))
+        .map(|(label, _)| label.clone())
+        .collect()
+}
@@ -9,6 +9,12 @@ interface FileChangeEvent {
 interface AppSettings {
   watch_dir: string;
 }
+interface SettingsNotice {
+  kind: 'migrated' | 'recovered';
+  message: string;
+  from_version: number | null;
+  backup_path: string | null;
+}
 
 listen<FileChangeEvent>('file-changed', (event) => {
   console.log(`[${event.payload.chat_type}] File changed: ${event.payload.path}, line: ${event.payload.line}`);
@@ -147,6 +153,49 @@ listen<AppSettings>('settings-updated', (event) => {
   updateWatchDirDisplay(event.payload.watch_dir);
 });
 
+// Tells the user at startup when their settings file was upgraded or reset,
+// whether or not they open the settings window.
+function showSettingsNotice(notice: SettingsNotice) {
+  const banner = document.createElement('div');
+  banner.style.cssText = `
+    display: flex;
+    align-items: center;
+    gap: 8px;
+    margin: 10px;
+    padding: 8px 12px;
+    border-radius: 4px;
+    font-size: 13px;
+    color: #333;
+    background-color: ${notice.kind === 'recovered' ? '#FFE0B2' : '#E3F2FD'};
+  `;
+
+  const text = document.createElement('span');
+  const backup = notice.backup_path ? ` A copy was kept at ${notice.backup_path}.` : '';
+  text.textContent = `${notice.message}${backup}`;
+  text.style.flex = '1';
+
+  const dismissButton = document.createElement('button');
+  dismissButton.textContent = 'Dismiss';
+  dismissButton.style.cssText = buttonStyle;
+  dismissButton.style.padding = '4px 10px';
+  dismissButton.addEventListener('click', () => banner.remove());
+
+  banner.appendChild(text);
+  banner.appendChild(dismissButton);
+  app.insertBefore(banner, controls.nextSibling);
+}
+
+void (async () => {
+  try {
+    const notice = await invoke<SettingsNotice | null>('take_settings_notice');
+    if (notice) {
+      showSettingsNotice(notice);
+    }
+  } catch (error) {
+    console.error('Failed to load settings notice:', error);
+  }
+})();
+
 void (async () => {
   try {
     const settings = await invoke<AppSettings>('get_settings');
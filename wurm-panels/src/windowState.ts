@@ -0,0 +1,26 @@
+import { invoke } from '@tauri-apps/api/core'
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow'
+
+type WindowState = {
+    opacity?: number | null
+}
+
+// Panels fade themselves, as not every platform has native window opacity.
+function applyOpacity(opacity: number | null | undefined) {
+    document.documentElement.style.opacity = String(opacity ?? 1)
+}
+
+const currentWindow = getCurrentWebviewWindow()
+
+void currentWindow.listen<WindowState>('window-state', (event) => {
+    applyOpacity(event.payload.opacity)
+})
+
+void currentWindow.listen<number>('window-opacity', (event) => {
+    applyOpacity(event.payload)
+})
+
+// The state sent when the window opened may arrive before these listeners.
+invoke<WindowState>('get_window_state', { windowLabel: currentWindow.label })
+    .then((state) => applyOpacity(state.opacity))
+    .catch((error) => console.error('Failed to load window state:', error))